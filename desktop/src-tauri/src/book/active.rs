@@ -1,10 +1,10 @@
 use crate::book::cover::Cover;
-use crate::book::handle::PageMap;
+use crate::book::handle::{BookHandle, PageMap};
 use crate::book::title::Title;
-use crate::database::model::Book;
+use crate::database::model::{Book, TrashedPage};
 use crate::event::Event;
-use crate::library;
 use crate::prelude::*;
+use crate::{library, trash};
 use image::ImageFormat;
 use natord::compare_ignore_case;
 use std::cmp::Ordering;
@@ -91,6 +91,70 @@ impl ActiveBook {
     pages.await.map(Arc::clone)
   }
 
+  /// Page where the reader was left off, if the book is in the library.
+  /// The name is preferred, as the index may have shifted if pages were added or removed.
+  pub async fn last_page(&self) -> Result<Option<usize>> {
+    let Ok(id) = self.try_id().await else {
+      return Ok(None);
+    };
+
+    let book = self
+      .app
+      .database_handle()
+      .get_book_by_id(id)
+      .await?;
+
+    let pages = self.pages().await?;
+    let by_name = book.last_page_name.as_deref().and_then(|name| {
+      pages
+        .iter()
+        .find(|(_, page)| page.as_str() == name)
+        .map(|(index, _)| *index)
+    });
+
+    let by_index = book
+      .last_page_index
+      .and_then(|it| usize::try_from(it).ok())
+      .map(|it| it.min(pages.len().saturating_sub(1)));
+
+    Ok(by_name.or(by_index))
+  }
+
+  /// Write the reading progress to the book metadata, unless it's already there.
+  /// Doing it on every page turn would mean rewriting the whole archive each time.
+  pub async fn save_progress(&self) -> Result<()> {
+    let Some(id) = self.id() else {
+      return Ok(());
+    };
+
+    let book = self
+      .app
+      .database_handle()
+      .get_book_by_id(id)
+      .await?;
+
+    let Some(index) = book.last_page_index else {
+      return Ok(());
+    };
+
+    let is_saved = self
+      .app
+      .book_handle()
+      .get_metadata(&self.path)
+      .await?
+      .and_then(|it| it.progress)
+      .is_some_and(|it| {
+        i32::try_from(it.page_index).is_ok_and(|it| it == index)
+          && book.last_page_name.as_ref() == Some(&it.page_name)
+      });
+
+    if !is_saved {
+      book.save_as_metadata(&self.app).await?;
+    }
+
+    Ok(())
+  }
+
   pub async fn has_page(&self, name: &str) -> Result<bool> {
     self
       .pages()
@@ -159,6 +223,7 @@ impl ActiveBook {
     Event::CoverExtracted { id, path }.emit(&self.app)
   }
 
+  /// Delete the page from the book, keeping a copy of it in the trash.
   pub async fn delete_page(&mut self, name: &str) -> Result<()> {
     // `ActiveBook::get_cover_name` will always fail if the book isn't in the library.
     let is_cover = match self.get_cover_name().await {
@@ -166,11 +231,33 @@ impl ActiveBook {
       Err(_) => self.id().is_some(),
     };
 
-    self
+    let index = self
+      .pages()
+      .await?
+      .iter()
+      .find(|(_, page)| page.as_str() == name)
+      .map(|(index, _)| *index)
+      .ok_or_else(|| err!(PageNotFound, "{name}"))?;
+
+    let bytes = self.get_page_as_bytes(name).await?;
+    let was_cover = is_cover && self.id().is_some();
+    let trashed = trash::put(&self.app, &self.path, name, index, was_cover, &bytes).await?;
+
+    let backup = BookHandle::is_backup_enabled(&self.app)?;
+    let result = self
       .app
       .book_handle()
-      .delete_page(&self.path, name)
-      .await?;
+      .delete_page(&self.path, name, backup)
+      .await;
+
+    // The page is still in the book, so there's nothing to restore.
+    if let Err(err) = result {
+      trash::purge(&self.app, trashed.id)
+        .await
+        .into_err_log(&self.app);
+
+      return Err(err);
+    }
 
     // As the page has been removed, we need to reset the cell.
     self.pages.take();
@@ -182,6 +269,8 @@ impl ActiveBook {
         return library::remove(&self.app, id).await;
       }
 
+      self.update_hash(id).await?;
+
       // Update with a new cover if it was the deleted page.
       if is_cover {
         let first_page = self
@@ -200,6 +289,58 @@ impl ActiveBook {
 
     Ok(())
   }
+
+  /// Forget the pages, so that they're read again from the book after it changed.
+  pub fn reset_pages(&mut self) {
+    self.pages.take();
+  }
+
+  /// Put a page from the trash back in the book.
+  /// If it was the cover, it becomes the cover again, undoing what [`ActiveBook::delete_page`] did.
+  pub async fn restore_page(&mut self, page: &TrashedPage) -> Result<()> {
+    let bytes = trash::read(&self.app, page).await?;
+    let backup = BookHandle::is_backup_enabled(&self.app)?;
+    self
+      .app
+      .book_handle()
+      .restore_page(&self.path, &page.page_name, bytes, backup)
+      .await?;
+
+    self.pages.take();
+
+    let Ok(id) = self.try_id().await else {
+      return Ok(());
+    };
+
+    self.update_hash(id).await?;
+
+    if page.was_cover {
+      self
+        .app
+        .database_handle()
+        .update_book_cover(id, &page.page_name)
+        .await?;
+    }
+
+    Ok(())
+  }
+
+  /// The hash is computed from the pages, so it must follow whenever they change.
+  /// Otherwise, the book could no longer be told apart from its duplicates, or found once moved.
+  async fn update_hash(&self, id: i32) -> Result<()> {
+    let hash = self
+      .app
+      .book_handle()
+      .get_hash(&self.path)
+      .await?;
+
+    self
+      .app
+      .database_handle()
+      .update_book_hash(id, &hash)
+      .await
+      .map(drop)
+  }
 }
 
 impl Drop for ActiveBook {
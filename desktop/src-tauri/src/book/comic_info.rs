@@ -0,0 +1,568 @@
+use crate::prelude::*;
+use quick_xml::events::{BytesDecl, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+
+/// Elements of the schema in the order they must appear, as `ComicInfo` is a `xs:sequence`.
+const SCHEMA_ORDER: &[&str] = &[
+  "Title",
+  "Series",
+  "Number",
+  "Count",
+  "Volume",
+  "AlternateSeries",
+  "AlternateNumber",
+  "AlternateCount",
+  "Summary",
+  "Notes",
+  "Year",
+  "Month",
+  "Day",
+  "Writer",
+  "Penciller",
+  "Inker",
+  "Colorist",
+  "Letterer",
+  "CoverArtist",
+  "Editor",
+  "Translator",
+  "Publisher",
+  "Imprint",
+  "Genre",
+  "Tags",
+  "Web",
+  "PageCount",
+  "LanguageISO",
+  "Format",
+  "BlackAndWhite",
+  "Manga",
+  "Characters",
+  "Teams",
+  "Locations",
+  "ScanInformation",
+  "StoryArc",
+  "StoryArcNumber",
+  "SeriesGroup",
+  "AgeRating",
+  "Pages",
+  "CommunityRating",
+  "MainCharacterOrTeam",
+  "Review",
+  "GTIN",
+];
+
+const PAGES: &str = "Pages";
+const XMLNS_XSD: &str = "http://www.w3.org/2001/XMLSchema";
+const XMLNS_XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Subset of the [ComicInfo](https://anansi-project.github.io/docs/comicinfo/intro) schema
+/// that Kotori understands. Any other field is ignored.
+#[derive(Clone, Debug, Default)]
+pub struct ComicInfo {
+  pub title: Option<String>,
+  pub series: Option<String>,
+  pub number: Option<String>,
+  pub volume: Option<i32>,
+  pub summary: Option<String>,
+  pub writer: Option<String>,
+  pub language: Option<String>,
+  pub manga: Option<Manga>,
+  pub genre: Option<String>,
+  pub tags: Option<String>,
+  pub community_rating: Option<f32>,
+  pub pages: Vec<ComicPage>,
+}
+
+impl ComicInfo {
+  const WRITE_KEY: &str = "writeComicInfo";
+
+  /// Whether Kotori should also write a `ComicInfo.xml` when saving the metadata of a book.
+  pub fn is_write_enabled(app: &AppHandle) -> Result<bool> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        let enabled = store
+          .get(Self::WRITE_KEY)
+          .and_then(|it| it.as_bool())
+          .unwrap_or(false);
+
+        Ok(enabled)
+      })
+      .map_err(Into::into)
+  }
+
+  pub fn set_write_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| store.set(Self::WRITE_KEY, enabled.into()))
+      .map_err(Into::into)
+  }
+
+  pub fn from_xml(bytes: &[u8]) -> Result<Self> {
+    let mut reader = Reader::from_reader(bytes);
+    reader.config_mut().trim_text(true);
+
+    let mut info = Self::default();
+    let mut current: Option<Vec<u8>> = None;
+
+    loop {
+      match reader.read_event()? {
+        Event::Start(element) | Event::Empty(element)
+          if element.local_name().as_ref() == b"Page" =>
+        {
+          info.push_page(&element)?;
+        }
+        Event::Start(element) => {
+          current = Some(element.local_name().as_ref().to_vec());
+        }
+        Event::End(_) => current = None,
+        Event::Text(text) => {
+          if let Some(name) = &current {
+            info.set_field(name, text.unescape()?.trim());
+          }
+        }
+        Event::Eof => break,
+        _ => {}
+      }
+    }
+
+    Ok(info)
+  }
+
+  fn set_field(&mut self, name: &[u8], value: &str) {
+    if value.is_empty() {
+      return;
+    }
+
+    let value = value.to_owned();
+    match name {
+      b"Title" => self.title = Some(value),
+      b"Series" => self.series = Some(value),
+      b"Number" => self.number = Some(value),
+      b"Volume" => self.volume = value.parse().ok(),
+      b"Summary" => self.summary = Some(value),
+      b"Writer" => self.writer = Some(value),
+      b"LanguageISO" => self.language = Some(value),
+      b"Manga" => self.manga = Manga::from_str(&value).ok(),
+      b"Genre" => self.genre = Some(value),
+      b"Tags" => self.tags = Some(value),
+      b"CommunityRating" => self.community_rating = value.parse().ok(),
+      _ => {}
+    }
+  }
+
+  fn push_page(&mut self, element: &BytesStart) -> Result<()> {
+    let mut image = None;
+    let mut kind = None;
+
+    for attribute in element.attributes() {
+      let attribute = attribute.map_err(quick_xml::Error::from)?;
+      let value = attribute.unescape_value()?;
+      match attribute.key.local_name().as_ref() {
+        b"Image" => image = value.parse().ok(),
+        b"Type" => kind = Some(value.into_owned()),
+        _ => {}
+      }
+    }
+
+    if let Some(image) = image {
+      self.pages.push(ComicPage { image, kind });
+    }
+
+    Ok(())
+  }
+
+  /// Index of the page marked as the front cover, if any.
+  pub fn front_cover(&self) -> Option<usize> {
+    self
+      .pages
+      .iter()
+      .find(|it| it.kind.as_deref() == Some(ComicPage::FRONT_COVER))
+      .map(|it| it.image)
+  }
+
+  /// Names in both `Genre` and `Tags`, which are comma-separated lists.
+  pub fn tag_names(&self) -> Vec<String> {
+    split_names([&self.genre, &self.tags])
+  }
+
+  /// Names in `Genre` alone.
+  pub fn genre_names(&self) -> Vec<String> {
+    split_names([&self.genre])
+  }
+
+  /// Community rating rounded to Kotori's rating scale.
+  #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+  pub fn rating(&self) -> Option<u8> {
+    self
+      .community_rating
+      .filter(|it| (0.0..=5.0).contains(it))
+      .map(|it| it.round() as u8)
+  }
+}
+
+fn split_names<'a, I>(lists: I) -> Vec<String>
+where
+  I: IntoIterator<Item = &'a Option<String>>,
+{
+  lists
+    .into_iter()
+    .flatten()
+    .flat_map(|it| it.split(','))
+    .map(str::trim)
+    .filter(|it| !it.is_empty())
+    .unique_by(|it| it.to_lowercase())
+    .map(ToOwned::to_owned)
+    .collect()
+}
+
+/// Changes to apply to a `ComicInfo.xml`.
+///
+/// Only the elements touched by the patch are rewritten.
+/// Everything else, including fields Kotori doesn't know about, is kept as it is.
+#[derive(Debug, Default)]
+pub struct ComicInfoPatch {
+  fields: Vec<(&'static str, Option<String>)>,
+  front_cover: Option<usize>,
+}
+
+impl ComicInfoPatch {
+  /// Set the text of an element, or remove it if `value` is `None`.
+  #[must_use]
+  pub fn field(mut self, name: &'static str, value: Option<String>) -> Self {
+    self.fields.retain(|(it, _)| *it != name);
+    self.fields.push((name, value));
+    self
+  }
+
+  /// Mark the page with the given index as the front cover.
+  #[must_use]
+  pub fn front_cover(mut self, image: usize) -> Self {
+    self.front_cover = Some(image);
+    self
+  }
+
+  /// Apply the patch to an existing document, or create a new one if there's none.
+  pub fn apply(&self, existing: Option<&[u8]>) -> Result<Vec<u8>> {
+    if let Some(bytes) = existing {
+      let mut writer = Writer::new(Vec::new());
+      self.rewrite(bytes, &mut writer)?;
+      Ok(writer.into_inner())
+    } else {
+      let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+      self.create(&mut writer)?;
+      Ok(writer.into_inner())
+    }
+  }
+
+  fn create(&self, writer: &mut Writer<Vec<u8>>) -> Result<()> {
+    let decl = BytesDecl::new("1.0", Some("utf-8"), None);
+    writer.write_event(Event::Decl(decl))?;
+
+    let root = BytesStart::new("ComicInfo")
+      .with_attributes([("xmlns:xsd", XMLNS_XSD), ("xmlns:xsi", XMLNS_XSI)]);
+
+    writer.write_event(Event::Start(root.borrow()))?;
+    for name in self.pending() {
+      self.write_element(writer, name)?;
+    }
+
+    writer.write_event(Event::End(root.to_end()))?;
+
+    Ok(())
+  }
+
+  /// Stream the existing document to the writer, replacing the elements in the patch.
+  /// Those missing from the document are inserted where the schema expects them.
+  fn rewrite(&self, bytes: &[u8], writer: &mut Writer<Vec<u8>>) -> Result<()> {
+    // Elements already in the document are replaced in place, wherever they are.
+    let existing = top_level_elements(bytes)?;
+    let mut pending = self.pending();
+    pending.retain(|it| !existing.iter().any(|name| name == it.as_bytes()));
+
+    let mut reader = Reader::from_reader(bytes);
+    let mut depth = 0_usize;
+
+    loop {
+      match reader.read_event()? {
+        Event::Start(element) if depth == 1 => {
+          let name = element.local_name();
+          self.write_pending_before(writer, &mut pending, name.as_ref())?;
+
+          if let Some(field) = self.find_field(name.as_ref()) {
+            reader.read_to_end(element.name())?;
+            self.write_element(writer, field)?;
+          } else if name.as_ref() == PAGES.as_bytes()
+            && let Some(cover) = self.front_cover
+          {
+            writer.write_event(Event::Start(element))?;
+            rewrite_pages(&mut reader, writer, cover)?;
+          } else {
+            depth += 1;
+            writer.write_event(Event::Start(element))?;
+          }
+        }
+        Event::Empty(element) if depth == 1 => {
+          let name = element.local_name();
+          self.write_pending_before(writer, &mut pending, name.as_ref())?;
+
+          if let Some(field) = self.find_field(name.as_ref()) {
+            self.write_element(writer, field)?;
+          } else if name.as_ref() == PAGES.as_bytes() && self.front_cover.is_some() {
+            self.write_element(writer, PAGES)?;
+          } else {
+            writer.write_event(Event::Empty(element))?;
+          }
+        }
+        // An empty root, such as `<ComicInfo/>`, still needs to hold the patched elements.
+        Event::Empty(element) if depth == 0 => {
+          writer.write_event(Event::Start(element.borrow()))?;
+          for name in pending.drain(..) {
+            self.write_element(writer, name)?;
+          }
+
+          writer.write_event(Event::End(element.to_end()))?;
+        }
+        Event::Start(element) => {
+          depth += 1;
+          writer.write_event(Event::Start(element))?;
+        }
+        Event::End(element) => {
+          depth = depth.saturating_sub(1);
+          if depth == 0 {
+            for name in pending.drain(..) {
+              writer.write_event(Event::Text(BytesText::new("  ")))?;
+              self.write_element(writer, name)?;
+              writer.write_event(Event::Text(BytesText::new("\n")))?;
+            }
+          }
+
+          writer.write_event(Event::End(element))?;
+        }
+        Event::Eof => break,
+        event => writer.write_event(event)?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Names of the elements the patch will write, in schema order.
+  fn pending(&self) -> Vec<&'static str> {
+    let mut pending = self
+      .fields
+      .iter()
+      .filter(|(_, value)| value.is_some())
+      .map(|(name, _)| *name)
+      .collect_vec();
+
+    if self.front_cover.is_some() {
+      pending.push(PAGES);
+    }
+
+    pending.sort_by_key(|it| schema_position(it.as_bytes()));
+    pending
+  }
+
+  /// Write the pending elements that must come before `next`.
+  fn write_pending_before(
+    &self,
+    writer: &mut Writer<Vec<u8>>,
+    pending: &mut Vec<&'static str>,
+    next: &[u8],
+  ) -> Result<()> {
+    let Some(position) = schema_position(next) else {
+      return Ok(());
+    };
+
+    while let Some(name) = pending.first().copied()
+      && schema_position(name.as_bytes()).is_some_and(|it| it < position)
+    {
+      pending.remove(0);
+      self.write_element(writer, name)?;
+      writer.write_event(Event::Text(BytesText::new("\n  ")))?;
+    }
+
+    Ok(())
+  }
+
+  fn find_field(&self, name: &[u8]) -> Option<&'static str> {
+    self
+      .fields
+      .iter()
+      .find(|(it, _)| it.as_bytes() == name)
+      .map(|(it, _)| *it)
+  }
+
+  fn write_element(&self, writer: &mut Writer<Vec<u8>>, name: &str) -> Result<()> {
+    if name == PAGES {
+      if let Some(cover) = self.front_cover {
+        writer
+          .create_element(PAGES)
+          .write_inner_content::<_, quick_xml::Error>(|writer| {
+            writer.write_event(Event::Empty(cover_page(cover)))
+          })?;
+      }
+    } else if let Some((_, Some(value))) = self.fields.iter().find(|(it, _)| *it == name) {
+      writer
+        .create_element(name)
+        .write_text_content(BytesText::new(value))?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Copy the pages to the writer, consuming the reader up to the end of the `Pages` element.
+/// The `FrontCover` type is moved to the page with the `cover` index, adding it if needed.
+fn rewrite_pages(
+  reader: &mut Reader<&[u8]>,
+  writer: &mut Writer<Vec<u8>>,
+  cover: usize,
+) -> Result<()> {
+  let mut depth = 0_usize;
+  let mut found = false;
+
+  loop {
+    match reader.read_event()? {
+      Event::Start(page) if depth == 0 && page.local_name().as_ref() == b"Page" => {
+        depth += 1;
+        let page = patch_page(&page, cover, &mut found)?;
+        writer.write_event(Event::Start(page))?;
+      }
+      Event::Empty(page) if depth == 0 && page.local_name().as_ref() == b"Page" => {
+        let page = patch_page(&page, cover, &mut found)?;
+        writer.write_event(Event::Empty(page))?;
+      }
+      Event::Start(element) => {
+        depth += 1;
+        writer.write_event(Event::Start(element))?;
+      }
+      Event::End(element) if depth == 0 => {
+        if !found {
+          writer.write_event(Event::Empty(cover_page(cover)))?;
+        }
+
+        writer.write_event(Event::End(element))?;
+        break;
+      }
+      Event::End(element) => {
+        depth -= 1;
+        writer.write_event(Event::End(element))?;
+      }
+      Event::Eof => break,
+      event => writer.write_event(event)?,
+    }
+  }
+
+  Ok(())
+}
+
+/// Names of the direct children of the root element.
+fn top_level_elements(bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+  let mut reader = Reader::from_reader(bytes);
+  let mut elements = Vec::new();
+  let mut depth = 0_usize;
+
+  loop {
+    match reader.read_event()? {
+      Event::Start(element) => {
+        if depth == 1 {
+          elements.push(element.local_name().as_ref().to_vec());
+        }
+
+        depth += 1;
+      }
+      Event::Empty(element) if depth == 1 => {
+        elements.push(element.local_name().as_ref().to_vec());
+      }
+      Event::End(_) => depth = depth.saturating_sub(1),
+      Event::Eof => break,
+      _ => {}
+    }
+  }
+
+  Ok(elements)
+}
+
+fn patch_page(page: &BytesStart, cover: usize, found: &mut bool) -> Result<BytesStart<'static>> {
+  let name = String::from_utf8_lossy(page.name().as_ref()).into_owned();
+  let mut patched = BytesStart::new(name);
+  let mut image = None;
+  let mut kind = None;
+
+  for attribute in page.attributes() {
+    let attribute = attribute.map_err(quick_xml::Error::from)?;
+    match attribute.key.local_name().as_ref() {
+      b"Type" => kind = Some(attribute.unescape_value()?.into_owned()),
+      key => {
+        if key == b"Image" {
+          image = attribute.unescape_value()?.parse::<usize>().ok();
+        }
+
+        patched.push_attribute(attribute);
+      }
+    }
+  }
+
+  if image == Some(cover) {
+    *found = true;
+    patched.push_attribute(("Type", ComicPage::FRONT_COVER));
+  } else if let Some(kind) = kind
+    && kind != ComicPage::FRONT_COVER
+  {
+    patched.push_attribute(("Type", kind.as_str()));
+  }
+
+  Ok(patched)
+}
+
+fn cover_page(cover: usize) -> BytesStart<'static> {
+  let image = cover.to_string();
+  let mut page = BytesStart::new("Page");
+  page.push_attribute(("Image", image.as_str()));
+  page.push_attribute(("Type", ComicPage::FRONT_COVER));
+  page
+}
+
+fn schema_position(name: &[u8]) -> Option<usize> {
+  SCHEMA_ORDER
+    .iter()
+    .position(|it| it.as_bytes() == name)
+}
+
+#[derive(Clone, Debug)]
+pub struct ComicPage {
+  /// Index of the page in the book.
+  pub image: usize,
+  pub kind: Option<String>,
+}
+
+impl ComicPage {
+  pub const FRONT_COVER: &'static str = "FrontCover";
+}
+
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq)]
+pub enum Manga {
+  Unknown,
+  No,
+  Yes,
+  YesAndRightToLeft,
+}
+
+impl Manga {
+  pub fn is_manga(self) -> Option<bool> {
+    match self {
+      Self::Unknown => None,
+      Self::No => Some(false),
+      Self::Yes | Self::YesAndRightToLeft => Some(true),
+    }
+  }
+
+  pub fn is_right_to_left(self) -> bool {
+    self == Self::YesAndRightToLeft
+  }
+}
@@ -1,30 +1,90 @@
+use crate::book::handle::cache::BookCache;
 use crate::book::handle::file::BookFile;
 use crate::book::handle::message::Message;
+use crate::book::handle::sidecar::{self, MetadataStorage};
+use crate::book::metadata::Metadata;
 use crate::prelude::*;
 use ahash::{HashMap, HashMapExt};
 use std::fmt;
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+/// How long to wait for more changes to the metadata of a book before writing it.
+const WRITE_DELAY: Duration = Duration::from_millis(750);
 
 pub(super) struct Actor {
-  cache: HashMap<PathBuf, BookFile>,
+  app: AppHandle,
+  cache: BookCache,
+  pending: HashMap<PathBuf, PendingWrite>,
   receiver: mpsc::Receiver<Message>,
+  /// Where sidecars are kept when the metadata is stored in the app data directory.
+  sidecar_dir: PathBuf,
+}
+
+/// Metadata waiting to be written, replaced by whatever comes next for the same book.
+struct PendingWrite {
+  metadata: Metadata,
+  comic_info: bool,
+  backup: bool,
+  storage: MetadataStorage,
+  due: Instant,
 }
 
 impl Actor {
-  pub(super) fn new(receiver: mpsc::Receiver<Message>) -> Self {
-    Self { cache: HashMap::new(), receiver }
+  pub(super) fn new(
+    app: AppHandle,
+    receiver: mpsc::Receiver<Message>,
+    sidecar_dir: PathBuf,
+  ) -> Self {
+    Self {
+      app,
+      cache: BookCache::new(),
+      pending: HashMap::new(),
+      receiver,
+      sidecar_dir,
+    }
   }
 
   pub(super) fn run(&mut self) {
-    while let Ok(message) = self.receiver.recv() {
-      self.handle_message(message);
+    loop {
+      let next_due = self
+        .pending
+        .values()
+        .map(|it| it.due)
+        .chain(self.cache.idle_deadline())
+        .min();
+
+      let message = match next_due {
+        Some(due) => {
+          let timeout = due.saturating_duration_since(Instant::now());
+          match self.receiver.recv_timeout(timeout) {
+            Ok(message) => Some(message),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => break,
+          }
+        }
+        None => match self.receiver.recv() {
+          Ok(message) => Some(message),
+          Err(_) => break,
+        },
+      };
+
+      if let Some(message) = message {
+        self.handle_message(message);
+      }
+
+      self.write_due();
+      self.cache.evict_idle();
     }
+
+    self.write_all();
   }
 
   #[cfg_attr(feature = "tracing", instrument)]
   fn handle_message(&mut self, message: Message) {
     #[cfg(feature = "tracing")]
-    trace!(actor_cache_size = self.cache.len());
+    trace!(book_cache = ?self.cache.stats());
 
     match message {
       Message::Close { path, nt } => {
@@ -48,30 +108,78 @@ impl Actor {
 
         let _ = tx.send(result);
       }
-      Message::DeletePage { path, page, tx } => {
+      Message::DeletePage { path, page, backup, tx } => {
         #[cfg(feature = "tracing")]
         trace!(delete_page = %page);
 
+        self.write_pending(&path);
+
+        let result = self
+          .remove_book(&path)
+          .and_then(|it| it.delete_page(&page, backup));
+
+        let _ = tx.send(result);
+      }
+      Message::RestorePage { path, page, bytes, backup, tx } => {
+        #[cfg(feature = "tracing")]
+        trace!(restore_page = %page);
+
+        self.write_pending(&path);
+
         let result = self
           .remove_book(&path)
-          .and_then(|it| it.delete_page(&page));
+          .and_then(|it| it.restore_page(&page, &bytes, backup));
 
         let _ = tx.send(result);
       }
-      Message::GetMetadata { path, tx } => {
+      Message::GetMetadata { path, storage, tx } => {
+        self.write_pending(&path);
+        let result = self.read_metadata(&path, storage);
+
+        let _ = tx.send(result);
+      }
+      Message::GetComicInfo { path, tx } => {
+        self.write_pending(&path);
         let result = self
           .get_book_mut(&path)
-          .and_then(BookFile::read_metadata);
+          .and_then(BookFile::read_comic_info);
 
         let _ = tx.send(result);
       }
-      Message::SetMetadata { path, metadata, tx } => {
+      Message::SetMetadata {
+        path,
+        metadata,
+        comic_info,
+        backup,
+        storage,
+        tx,
+      } => {
         #[cfg(feature = "tracing")]
         trace!(set_metadata = ?metadata);
 
-        let result = self
-          .remove_book(&path)
-          .and_then(|it| it.write_metadata(&metadata));
+        // What was waiting is never written, as the new metadata already includes it.
+        let write = PendingWrite {
+          metadata,
+          comic_info,
+          backup,
+          storage,
+          due: Instant::now() + WRITE_DELAY,
+        };
+
+        self.pending.insert(path, write);
+
+        // Callers shouldn't wait for the delay, so they're answered once the write is queued.
+        let _ = tx.send(Ok(()));
+      }
+      Message::GetCacheStats { tx } => {
+        let _ = tx.send(Ok(self.cache.stats()));
+      }
+      Message::Flush { nt } => {
+        self.write_all();
+        nt.notify_one();
+      }
+      Message::GetHash { path, tx } => {
+        let result = self.get_book_mut(&path).and_then(BookFile::hash);
 
         let _ = tx.send(result);
       }
@@ -85,31 +193,89 @@ impl Actor {
     };
   }
 
-  fn ensure_cache_contains(&mut self, path: &Path) -> Result<()> {
-    if !self.cache.contains_key(path) {
-      let book = BookFile::open(path)?;
-      self.cache.insert(path.to_path_buf(), book);
+  /// Write the metadata of every book that has waited long enough.
+  fn write_due(&mut self) {
+    let now = Instant::now();
+    let due = self
+      .pending
+      .iter()
+      .filter(|(_, it)| it.due <= now)
+      .map(|(path, _)| path.clone())
+      .collect_vec();
+
+    for path in due {
+      self.write_pending(&path);
     }
+  }
 
-    Ok(())
+  fn write_all(&mut self) {
+    let paths = self.pending.keys().cloned().collect_vec();
+    for path in paths {
+      self.write_pending(&path);
+    }
   }
 
-  fn get_book(&mut self, path: &Path) -> Result<&BookFile> {
-    self.ensure_cache_contains(path)?;
+  /// Write the metadata waiting for the book, if any, so that what comes next sees it.
+  /// Nobody is waiting for the result anymore, so errors are only logged.
+  fn write_pending(&mut self, path: &Path) {
+    if let Some(write) = self.pending.remove(path) {
+      let result: Result<()> = try {
+        if let Some(sidecar) = self.sidecar_path(path, write.storage)? {
+          sidecar::write(&sidecar, &write.metadata)?;
+        } else {
+          self.remove_book(path)?.write_metadata(
+            &write.metadata,
+            write.comic_info,
+            write.backup,
+          )?;
+        }
+      };
+
+      // Some formats can't be written to, but the database is still the source of truth.
+      if let Err(Error::ReadOnlyBook(_)) = result {
+        #[cfg(feature = "tracing")]
+        debug!("skipping metadata for read-only book: {}", path.display());
+
+        return;
+      }
+
+      result.into_err_log(&self.app);
+    }
+  }
+
+  fn read_metadata(&mut self, path: &Path, storage: MetadataStorage) -> Result<Option<Metadata>> {
+    if let Some(sidecar) = self.sidecar_path(path, storage)?
+      && let Some(metadata) = sidecar::read(&sidecar)?
+    {
+      return Ok(Some(metadata));
+    }
+
+    // Books that had their metadata written before switching to a sidecar still have it.
     self
-      .cache
-      .get(path)
-      .map(Ok)
-      .expect("book should be in the cache")
+      .get_book_mut(path)
+      .and_then(BookFile::read_metadata)
+  }
+
+  /// Path of the sidecar for the book, or `None` if the metadata is stored inside it.
+  fn sidecar_path(&mut self, path: &Path, storage: MetadataStorage) -> Result<Option<PathBuf>> {
+    let sidecar = match storage {
+      MetadataStorage::Archive => None,
+      MetadataStorage::NextToBook => Some(sidecar::next_to(path)),
+      MetadataStorage::AppData => {
+        let hash = self.get_book_mut(path)?.hash()?;
+        Some(sidecar::in_dir(&self.sidecar_dir, &hash))
+      }
+    };
+
+    Ok(sidecar)
+  }
+
+  fn get_book(&mut self, path: &Path) -> Result<&BookFile> {
+    self.cache.get(path).map(|it| &*it)
   }
 
   fn get_book_mut(&mut self, path: &Path) -> Result<&mut BookFile> {
-    self.ensure_cache_contains(path)?;
-    self
-      .cache
-      .get_mut(path)
-      .map(Ok)
-      .expect("book should be in the cache")
+    self.cache.get(path)
   }
 
   fn remove_book(&mut self, path: &Path) -> Result<BookFile> {
@@ -124,7 +290,8 @@ impl Actor {
 impl fmt::Debug for Actor {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.debug_struct("Actor")
-      .field("cache", &self.cache.len())
+      .field("cache", &self.cache)
+      .field("pending", &self.pending.len())
       .finish_non_exhaustive()
   }
 }
@@ -0,0 +1,304 @@
+use crate::prelude::*;
+use chrono::{Datelike, Local, Timelike};
+use flate2::Crc;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const EOCD_SIZE: usize = 22;
+const ZIP64_LOCATOR_SIZE: usize = 20;
+
+/// Appended files are left behind as dead space once replaced,
+/// so larger ones are better off in a full rewrite. The central directory doesn't count,
+/// as it's written again in full either way.
+const MAX_FILES_SIZE: usize = 1024 * 1024;
+/// Replaced entries are left behind in the file,
+/// so it's eventually rewritten in full to get rid of them.
+const MAX_DEAD_SPACE: u64 = 4 * 1024 * 1024;
+
+/// Version 2.0 of the specification, the oldest one every reader understands.
+const VERSION: u16 = 20;
+const UTF8_FLAG: u16 = 1 << 11;
+const STORED: u16 = 0;
+
+struct EndOfCentralDirectory {
+  position: u64,
+  entries: u16,
+  size: u32,
+  offset: u32,
+}
+
+/// Append the files to the end of the zip archive, followed by a new central directory
+/// that leaves out the entries they replace. Nothing else in the archive is read or copied.
+///
+/// Returns `false` without touching the file if the archive can't be written to this way,
+/// such as when it needs Zip64 or has accumulated too many replaced entries,
+/// in which case it should be rewritten instead.
+pub(super) fn write_files(path: &Path, files: &[(&str, &[u8])], pages: usize) -> Result<bool> {
+  let mut file = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .open(path)?;
+
+  let len = file.metadata()?.len();
+  let Some(eocd) = find_eocd(&mut file, len)? else {
+    return Ok(false);
+  };
+
+  // Archives with something other than the central directory before the end record,
+  // like those with data prepended to them, are left for the zip crate to deal with.
+  if eocd.position != u64::from(eocd.offset) + u64::from(eocd.size) {
+    return Ok(false);
+  }
+
+  let mut central = vec![0; usize::try_from(eocd.size)?];
+  file.seek(SeekFrom::Start(u64::from(eocd.offset)))?;
+  file.read_exact(&mut central)?;
+
+  let Some(kept) = KeptEntries::from_central(&central, eocd.entries, files) else {
+    return Ok(false);
+  };
+
+  if u64::from(eocd.offset).saturating_sub(kept.live) > MAX_DEAD_SPACE {
+    return Ok(false);
+  }
+
+  let Some(tail) = build_tail(len, kept, files)? else {
+    return Ok(false);
+  };
+
+  file.seek(SeekFrom::End(0))?;
+  let result: Result<()> = try {
+    file.write_all(&tail)?;
+    file.sync_all()?;
+    super::verify(path, pages)?;
+  };
+
+  // The original content was never touched, so cutting what was appended restores it.
+  if let Err(err) = result {
+    let _ = file.set_len(len);
+    let _ = file.sync_all();
+    return Err(err);
+  }
+
+  Ok(true)
+}
+
+/// Entries in the central directory that aren't replaced by the files being written.
+struct KeptEntries {
+  records: Vec<u8>,
+  count: usize,
+  /// Roughly how many bytes in the file still belong to them.
+  live: u64,
+}
+
+impl KeptEntries {
+  fn from_central(central: &[u8], entries: u16, files: &[(&str, &[u8])]) -> Option<Self> {
+    let mut kept = Self {
+      records: Vec::with_capacity(central.len()),
+      count: 0,
+      live: 0,
+    };
+
+    let mut rest = central;
+    for _ in 0..entries {
+      if rest.len() < CENTRAL_HEADER_SIZE || read_u32(rest, 0) != CENTRAL_HEADER_SIGNATURE {
+        return None;
+      }
+
+      let compressed = read_u32(rest, 20);
+      let name_len = usize::from(read_u16(rest, 28));
+      let extra_len = usize::from(read_u16(rest, 30));
+      let comment_len = usize::from(read_u16(rest, 32));
+      let offset = read_u32(rest, 42);
+
+      // Zip64 entries keep their actual sizes and offsets elsewhere.
+      if compressed == u32::MAX || offset == u32::MAX {
+        return None;
+      }
+
+      let record_len = CENTRAL_HEADER_SIZE + name_len + extra_len + comment_len;
+      let record = rest.get(..record_len)?;
+      let name = &record[CENTRAL_HEADER_SIZE..CENTRAL_HEADER_SIZE + name_len];
+
+      if !files.iter().any(|(it, _)| it.as_bytes() == name) {
+        let header = u64::try_from(LOCAL_HEADER_SIZE + name_len + extra_len).ok()?;
+        kept.records.extend_from_slice(record);
+        kept.count += 1;
+        kept.live += header + u64::from(compressed);
+      }
+
+      rest = &rest[record_len..];
+    }
+
+    Some(kept)
+  }
+}
+
+/// Local headers and content of the files, then the new central directory and its end record.
+fn build_tail(len: u64, kept: KeptEntries, files: &[(&str, &[u8])]) -> Result<Option<Vec<u8>>> {
+  let (time, date) = dos_datetime();
+  let mut tail = Vec::new();
+  let mut central = kept.records;
+
+  for (name, bytes) in files {
+    let mut crc = Crc::new();
+    crc.update(bytes);
+    let crc = crc.sum();
+
+    let (Ok(offset), Ok(size), Ok(name_len)) = (
+      u32::try_from(len + u64::try_from(tail.len())?),
+      u32::try_from(bytes.len()),
+      u16::try_from(name.len()),
+    ) else {
+      return Ok(None);
+    };
+
+    put_u32(&mut tail, LOCAL_HEADER_SIGNATURE);
+    put_u16(&mut tail, VERSION);
+    put_u16(&mut tail, UTF8_FLAG);
+    put_u16(&mut tail, STORED);
+    put_u16(&mut tail, time);
+    put_u16(&mut tail, date);
+    put_u32(&mut tail, crc);
+    put_u32(&mut tail, size);
+    put_u32(&mut tail, size);
+    put_u16(&mut tail, name_len);
+    put_u16(&mut tail, 0);
+    tail.extend_from_slice(name.as_bytes());
+    tail.extend_from_slice(bytes);
+
+    put_u32(&mut central, CENTRAL_HEADER_SIGNATURE);
+    put_u16(&mut central, VERSION);
+    put_u16(&mut central, VERSION);
+    put_u16(&mut central, UTF8_FLAG);
+    put_u16(&mut central, STORED);
+    put_u16(&mut central, time);
+    put_u16(&mut central, date);
+    put_u32(&mut central, crc);
+    put_u32(&mut central, size);
+    put_u32(&mut central, size);
+    put_u16(&mut central, name_len);
+    put_u16(&mut central, 0);
+    put_u16(&mut central, 0);
+    put_u16(&mut central, 0);
+    put_u16(&mut central, 0);
+    put_u32(&mut central, 0);
+    put_u32(&mut central, offset);
+    central.extend_from_slice(name.as_bytes());
+  }
+
+  if tail.len() > MAX_FILES_SIZE {
+    return Ok(None);
+  }
+
+  let (Ok(entries), Ok(size), Ok(offset)) = (
+    u16::try_from(kept.count + files.len()),
+    u32::try_from(central.len()),
+    u32::try_from(len + u64::try_from(tail.len())?),
+  ) else {
+    return Ok(None);
+  };
+
+  tail.extend_from_slice(&central);
+
+  put_u32(&mut tail, EOCD_SIGNATURE);
+  put_u16(&mut tail, 0);
+  put_u16(&mut tail, 0);
+  put_u16(&mut tail, entries);
+  put_u16(&mut tail, entries);
+  put_u32(&mut tail, size);
+  put_u32(&mut tail, offset);
+  put_u16(&mut tail, 0);
+
+  Ok(Some(tail))
+}
+
+/// Find the end of central directory record, which is at the very end of the file
+/// unless the archive has a comment. Archives spanning many disks or using Zip64 are ignored.
+fn find_eocd(file: &mut File, len: u64) -> Result<Option<EndOfCentralDirectory>> {
+  let max_comment = u64::from(u16::MAX);
+  let tail_len = len.min(max_comment + u64::try_from(EOCD_SIZE)?);
+  let tail_start = len - tail_len;
+
+  let mut buf = vec![0; usize::try_from(tail_len)?];
+  file.seek(SeekFrom::Start(tail_start))?;
+  file.read_exact(&mut buf)?;
+
+  if buf.len() < EOCD_SIZE {
+    return Ok(None);
+  }
+
+  let start = (0..=buf.len() - EOCD_SIZE).rev().find(|&at| {
+    read_u32(&buf, at) == EOCD_SIGNATURE
+      && at + EOCD_SIZE + usize::from(read_u16(&buf, at + 20)) == buf.len()
+  });
+
+  let Some(start) = start else {
+    return Ok(None);
+  };
+
+  let is_zip64 = start
+    .checked_sub(ZIP64_LOCATOR_SIZE)
+    .is_some_and(|at| read_u32(&buf, at) == ZIP64_LOCATOR_SIGNATURE);
+
+  let disk = read_u16(&buf, start + 4);
+  let central_disk = read_u16(&buf, start + 6);
+  let disk_entries = read_u16(&buf, start + 8);
+  let entries = read_u16(&buf, start + 10);
+  let size = read_u32(&buf, start + 12);
+  let offset = read_u32(&buf, start + 16);
+
+  if is_zip64
+    || disk != 0
+    || central_disk != 0
+    || disk_entries != entries
+    || entries == u16::MAX
+    || size == u32::MAX
+    || offset == u32::MAX
+  {
+    return Ok(None);
+  }
+
+  Ok(Some(EndOfCentralDirectory {
+    position: tail_start + u64::try_from(start)?,
+    entries,
+    size,
+    offset,
+  }))
+}
+
+/// Current local time, in the format used by zip headers.
+fn dos_datetime() -> (u16, u16) {
+  let now = Local::now();
+  let time = (now.hour() << 11) | (now.minute() << 5) | (now.second() / 2);
+  let year = u32::try_from(now.year() - 1980).unwrap_or_default();
+  let date = (year << 9) | (now.month() << 5) | now.day();
+
+  (
+    u16::try_from(time).unwrap_or_default(),
+    u16::try_from(date).unwrap_or_default(),
+  )
+}
+
+fn read_u16(buf: &[u8], at: usize) -> u16 {
+  u16::from_le_bytes([buf[at], buf[at + 1]])
+}
+
+fn read_u32(buf: &[u8], at: usize) -> u32 {
+  u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+}
+
+fn put_u16(buf: &mut Vec<u8>, value: u16) {
+  buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+  buf.extend_from_slice(&value.to_le_bytes());
+}
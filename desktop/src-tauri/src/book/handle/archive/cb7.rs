@@ -0,0 +1,72 @@
+use super::BookArchive;
+use crate::bail;
+use crate::prelude::*;
+use ahash::HashMap;
+use sevenz_rust::{Password, SevenZReader};
+use std::io::{self, Read};
+
+/// 7-Zip archives can be read, but not written.
+pub(super) struct Cb7 {
+  path: PathBuf,
+  names: Vec<String>,
+  sizes: HashMap<String, u64>,
+}
+
+impl Cb7 {
+  pub(super) fn open(path: &Path) -> Result<Self> {
+    let reader = SevenZReader::open(path, Password::empty())?;
+    let files = reader
+      .archive()
+      .files
+      .iter()
+      .filter(|it| it.has_stream() && !it.is_directory())
+      .map(|it| (it.name().to_owned(), it.size()))
+      .collect_vec();
+
+    let names = files
+      .iter()
+      .map(|(name, _)| name.clone())
+      .collect();
+    let sizes = files.into_iter().collect();
+
+    Ok(Self { path: path.to_owned(), names, sizes })
+  }
+}
+
+impl BookArchive for Cb7 {
+  fn file_names(&self) -> Vec<String> {
+    self.names.clone()
+  }
+
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>> {
+    Ok(self.sizes.get(name).copied())
+  }
+
+  /// Entries in a solid block depend on the data before them,
+  /// so every entry up to the one we want must be decompressed.
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>> {
+    let mut bytes = None;
+    let mut reader = SevenZReader::open(&self.path, Password::empty())?;
+    reader.for_each_entries(|entry, data| {
+      if entry.name() == name {
+        let mut buf = Vec::new();
+        data.read_to_end(&mut buf)?;
+        bytes = Some(buf);
+        return Ok(false);
+      }
+
+      io::copy(data, &mut io::sink())?;
+      Ok(true)
+    })?;
+
+    Ok(bytes)
+  }
+
+  fn delete_file(self: Box<Self>, _: &str, _: bool) -> Result<()> {
+    bail!(ReadOnlyBook, "{}", self.path.display());
+  }
+
+  fn write_files(self: Box<Self>, _: &[(&str, &[u8])], _: bool) -> Result<()> {
+    bail!(ReadOnlyBook, "{}", self.path.display());
+  }
+}
@@ -0,0 +1,64 @@
+use super::BookArchive;
+use crate::bail;
+use crate::prelude::*;
+use ahash::{HashMap, HashMapExt};
+use unrar::Archive;
+
+/// RAR archives (v4 and v5) can be read, but not written.
+pub(super) struct Cbr {
+  path: PathBuf,
+  names: Vec<String>,
+  sizes: HashMap<String, u64>,
+}
+
+impl Cbr {
+  pub(super) fn open(path: &Path) -> Result<Self> {
+    let mut names = Vec::new();
+    let mut sizes = HashMap::new();
+    for entry in Archive::new(path).open_for_listing()? {
+      let entry = entry?;
+      if entry.is_file() {
+        let name = entry.filename.to_string_lossy().into_owned();
+        sizes.insert(name.clone(), entry.unpacked_size);
+        names.push(name);
+      }
+    }
+
+    Ok(Self { path: path.to_owned(), names, sizes })
+  }
+}
+
+impl BookArchive for Cbr {
+  fn file_names(&self) -> Vec<String> {
+    self.names.clone()
+  }
+
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>> {
+    Ok(self.sizes.get(name).copied())
+  }
+
+  /// Solid archives can only be decompressed sequentially,
+  /// so we need to walk through the headers until the file is found.
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>> {
+    let target = Path::new(name);
+    let mut archive = Archive::new(&self.path).open_for_processing()?;
+    while let Some(header) = archive.read_header()? {
+      if header.entry().filename.as_path() == target {
+        let (bytes, _) = header.read()?;
+        return Ok(Some(bytes));
+      }
+
+      archive = header.skip()?;
+    }
+
+    Ok(None)
+  }
+
+  fn delete_file(self: Box<Self>, _: &str, _: bool) -> Result<()> {
+    bail!(ReadOnlyBook, "{}", self.path.display());
+  }
+
+  fn write_files(self: Box<Self>, _: &[(&str, &[u8])], _: bool) -> Result<()> {
+    bail!(ReadOnlyBook, "{}", self.path.display());
+  }
+}
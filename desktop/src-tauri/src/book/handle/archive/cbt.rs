@@ -0,0 +1,133 @@
+use super::BookArchive;
+use crate::prelude::*;
+use ahash::{HashMap, HashMapExt};
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use tar::{Archive, Builder, Header};
+
+/// Tar archives, optionally compressed with gzip.
+pub(super) struct Cbt {
+  path: PathBuf,
+  names: Vec<String>,
+  sizes: HashMap<String, u64>,
+  gzip: bool,
+}
+
+impl Cbt {
+  pub(super) fn open(path: &Path, gzip: bool) -> Result<Self> {
+    let mut names = Vec::new();
+    let mut sizes = HashMap::new();
+    let mut archive = Archive::new(reader(path, gzip)?);
+    for entry in archive.entries()? {
+      let entry = entry?;
+      if entry.header().entry_type().is_file() {
+        let name = entry.path()?.to_string_lossy().into_owned();
+        sizes.insert(name.clone(), entry.size());
+        names.push(name);
+      }
+    }
+
+    Ok(Self {
+      path: path.to_owned(),
+      names,
+      sizes,
+      gzip,
+    })
+  }
+
+  /// Tar has no central directory, so the archive is rebuilt entry by entry,
+  /// skipping those named in `skip` and appending `files` at the end.
+  fn rewrite(&self, skip: &[&str], files: &[(&str, &[u8])], backup: bool) -> Result<()> {
+    let pages = super::count_pages(self.names.clone(), skip, files);
+    super::replace(&self.path, pages, backup, |file| {
+      if self.gzip {
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = Builder::new(encoder);
+        self.copy_into(&mut builder, skip, files)?;
+        builder.into_inner()?.finish()?;
+      } else {
+        let mut builder = Builder::new(file);
+        self.copy_into(&mut builder, skip, files)?;
+        builder.into_inner()?;
+      }
+
+      Ok(())
+    })
+  }
+
+  fn copy_into<W: Write>(
+    &self,
+    builder: &mut Builder<W>,
+    skip: &[&str],
+    files: &[(&str, &[u8])],
+  ) -> Result<()> {
+    let mut archive = Archive::new(reader(&self.path, self.gzip)?);
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      let path = entry.path()?.into_owned();
+      if skip.iter().any(|it| path == Path::new(it)) {
+        continue;
+      }
+
+      let mut header = entry.header().clone();
+      builder.append_data(&mut header, path, &mut entry)?;
+    }
+
+    for (name, bytes) in files {
+      let mut header = Header::new_gnu();
+      header.set_size(u64::try_from(bytes.len())?);
+      header.set_mode(0o644);
+      header.set_mtime(u64::try_from(Utc::now().timestamp()).unwrap_or_default());
+      builder.append_data(&mut header, name, *bytes)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl BookArchive for Cbt {
+  fn file_names(&self) -> Vec<String> {
+    self.names.clone()
+  }
+
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>> {
+    Ok(self.sizes.get(name).copied())
+  }
+
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>> {
+    let mut archive = Archive::new(reader(&self.path, self.gzip)?);
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      if entry.path()? == Path::new(name) {
+        let size = usize::try_from(entry.size()).unwrap_or_default();
+        let mut buf = Vec::with_capacity(size);
+        entry.read_to_end(&mut buf)?;
+        return Ok(Some(buf));
+      }
+    }
+
+    Ok(None)
+  }
+
+  fn delete_file(self: Box<Self>, name: &str, backup: bool) -> Result<()> {
+    self.rewrite(&[name], &[], backup)
+  }
+
+  fn write_files(self: Box<Self>, files: &[(&str, &[u8])], backup: bool) -> Result<()> {
+    let skip = files.iter().map(|(name, _)| *name).collect_vec();
+    self.rewrite(&skip, files, backup)
+  }
+}
+
+fn reader(path: &Path, gzip: bool) -> Result<Box<dyn Read>> {
+  let file = BufReader::new(File::open(path)?);
+  if gzip {
+    Ok(Box::new(GzDecoder::new(file)))
+  } else {
+    Ok(Box::new(file))
+  }
+}
@@ -0,0 +1,127 @@
+use super::BookArchive;
+use crate::prelude::*;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use zip::result::{ZipError, ZipResult};
+use zip::write::SimpleFileOptions as ZipSimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+pub(super) struct Cbz {
+  path: PathBuf,
+  zip: ZipArchive<File>,
+}
+
+impl Cbz {
+  pub(super) fn open(path: &Path) -> Result<Self> {
+    let reader = File::open(path)?;
+    let zip = ZipArchive::new(reader)?;
+    Ok(Self { path: path.to_owned(), zip })
+  }
+
+  /// Copy every file not named in `skip` to a new archive, appending `files` at the end.
+  fn rewrite(mut self, skip: &[&str], files: &[(&str, &[u8])], backup: bool) -> Result<()> {
+    let pages = super::count_pages(self.file_names(), skip, files);
+    let path = self.path.clone();
+    super::replace(&path, pages, backup, |file| {
+      let mut writer = ZipWriter::new(file);
+      self
+        .zip
+        .raw_copy_if(&mut writer, |it| !skip.contains(it))?;
+
+      for (name, bytes) in files {
+        writer.start_file(*name, ZipSimpleFileOptions::default())?;
+        writer.write_all(bytes)?;
+      }
+
+      writer.finish()?;
+      Ok(())
+    })
+  }
+}
+
+impl BookArchive for Cbz {
+  fn file_names(&self) -> Vec<String> {
+    self.zip.file_names().map_into().collect()
+  }
+
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>> {
+    match self.zip.by_name(name) {
+      Ok(it) => Ok(Some(it.size())),
+      Err(ZipError::FileNotFound) => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>> {
+    match self.zip.read_file(name) {
+      Ok(it) => Ok(Some(it)),
+      Err(ZipError::FileNotFound) => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn delete_file(self: Box<Self>, name: &str, backup: bool) -> Result<()> {
+    Cbz::rewrite(*self, &[name], &[], backup)
+  }
+
+  /// Files are appended to the archive whenever possible, as rewriting it would mean
+  /// copying every page just to change the metadata. As that writes to the original file,
+  /// the archive is always rewritten when a backup should be kept.
+  fn write_files(self: Box<Self>, files: &[(&str, &[u8])], backup: bool) -> Result<()> {
+    let skip = files.iter().map(|(name, _)| *name).collect_vec();
+    if !backup {
+      let pages = super::count_pages(self.file_names(), &skip, files);
+      if super::append::write_files(&self.path, files, pages)? {
+        return Ok(());
+      }
+    }
+
+    Cbz::rewrite(*self, &skip, files, backup)
+  }
+}
+
+trait ZipArchiveExt {
+  fn file_names_by<F>(&mut self, f: F) -> Vec<String>
+  where
+    F: FnMut(&&str) -> bool;
+
+  fn raw_copy_if<W, F>(&mut self, writer: &mut ZipWriter<&mut W>, f: F) -> ZipResult<()>
+  where
+    W: Write + Seek,
+    F: FnMut(&&str) -> bool;
+
+  fn read_file(&mut self, name: &str) -> ZipResult<Vec<u8>>;
+}
+
+impl<T> ZipArchiveExt for ZipArchive<T>
+where
+  T: Read + Seek,
+{
+  fn file_names_by<F>(&mut self, f: F) -> Vec<String>
+  where
+    F: FnMut(&&str) -> bool,
+  {
+    self.file_names().filter(f).map_into().collect()
+  }
+
+  fn read_file(&mut self, name: &str) -> ZipResult<Vec<u8>> {
+    let mut file = self.by_name(name)?;
+    let size = usize::try_from(file.size()).unwrap_or_default();
+    let mut buf = Vec::with_capacity(size);
+    file.read_to_end(&mut buf)?;
+    Ok(buf)
+  }
+
+  fn raw_copy_if<W, F>(&mut self, writer: &mut ZipWriter<&mut W>, f: F) -> ZipResult<()>
+  where
+    W: Write + Seek,
+    F: FnMut(&&str) -> bool,
+  {
+    for name in self.file_names_by(f) {
+      let file = self.by_name(&name)?;
+      writer.raw_copy_file(file)?;
+    }
+
+    Ok(())
+  }
+}
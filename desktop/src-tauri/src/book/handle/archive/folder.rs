@@ -0,0 +1,92 @@
+use super::BookArchive;
+use crate::fs::Tempfile;
+use crate::prelude::*;
+use std::fs;
+use std::io::Write;
+use std::path::Component;
+
+/// Plain folder of images, with any other file (like the metadata) kept alongside the pages.
+pub(super) struct ImageFolder {
+  path: PathBuf,
+  names: Vec<String>,
+}
+
+impl ImageFolder {
+  pub(super) fn open(path: &Path) -> Result<Self> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(path)? {
+      let entry = entry?;
+      if entry.file_type()?.is_file()
+        && let Ok(name) = entry.file_name().into_string()
+      {
+        names.push(name);
+      }
+    }
+
+    Ok(Self { path: path.to_owned(), names })
+  }
+
+  /// Resolve the name of a file directly inside the folder, refusing anything that could escape it.
+  fn resolve(&self, name: &str) -> Result<PathBuf> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+      (Some(Component::Normal(_)), None) => Ok(self.path.join(name)),
+      _ => Err(err!(InvalidPath, "{name}")),
+    }
+  }
+}
+
+impl BookArchive for ImageFolder {
+  fn file_names(&self) -> Vec<String> {
+    self.names.clone()
+  }
+
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>> {
+    let path = self.resolve(name)?;
+    if !path.try_exists()? {
+      return Ok(None);
+    }
+
+    fs::metadata(path)
+      .map(|it| Some(it.len()))
+      .map_err(Into::into)
+  }
+
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>> {
+    let path = self.resolve(name)?;
+    if !path.try_exists()? {
+      return Ok(None);
+    }
+
+    fs::read(path).map(Some).map_err(Into::into)
+  }
+
+  fn delete_file(self: Box<Self>, name: &str, backup: bool) -> Result<()> {
+    let path = self.resolve(name)?;
+    if backup {
+      super::keep_backup(&path)?;
+    }
+
+    fs::remove_file(path)?;
+    super::sync_dir(&self.path)
+  }
+
+  /// Each file is written in full to a temporary one before taking its place,
+  /// so a crash never leaves a page or the metadata half written.
+  fn write_files(self: Box<Self>, files: &[(&str, &[u8])], backup: bool) -> Result<()> {
+    for (name, bytes) in files {
+      let path = self.resolve(name)?;
+      let mut temp = Tempfile::new_in(&self.path)?;
+      temp.file.write_all(bytes)?;
+      temp.file.sync_all()?;
+
+      if backup && path.try_exists()? {
+        super::keep_backup(&path)?;
+      }
+
+      fs::rename(&temp.path, &path)?;
+    }
+
+    super::sync_dir(&self.path)
+  }
+}
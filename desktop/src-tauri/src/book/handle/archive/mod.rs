@@ -0,0 +1,222 @@
+mod append;
+mod cb7;
+mod cbr;
+mod cbt;
+mod cbz;
+mod folder;
+
+use super::PageMap;
+use crate::bail;
+use crate::fs::Tempfile;
+use crate::prelude::*;
+use crate::utils::glob;
+use cb7::Cb7;
+use cbr::Cbr;
+use cbt::Cbt;
+use cbz::Cbz;
+use flate2::read::GzDecoder;
+use folder::ImageFolder;
+use natord::compare_ignore_case;
+use std::fs::{self, File};
+use std::io::Read;
+
+/// Common interface for the archive formats a book can be stored in.
+pub(super) trait BookArchive: Send {
+  /// Names of all files in the archive, pages or not.
+  fn file_names(&self) -> Vec<String>;
+
+  /// Uncompressed size of the file with the given name, returning `None` if it doesn't exist.
+  fn file_size(&mut self, name: &str) -> Result<Option<u64>>;
+
+  /// Read the file with the given name, returning `None` if it doesn't exist.
+  fn read_file(&mut self, name: &str) -> Result<Option<Vec<u8>>>;
+
+  /// Rewrite the archive without the file with the given name.
+  /// The original is kept as a `.bak` if `backup` is `true`.
+  fn delete_file(self: Box<Self>, name: &str, backup: bool) -> Result<()>;
+
+  /// Rewrite the archive, adding the files or replacing those that already exist.
+  /// The original is kept as a `.bak` if `backup` is `true`.
+  fn write_files(self: Box<Self>, files: &[(&str, &[u8])], backup: bool) -> Result<()>;
+}
+
+const GZIP_MAGIC: &[u8] = b"\x1F\x8B";
+const RAR4_MAGIC: &[u8] = b"Rar!\x1A\x07\x00";
+const RAR5_MAGIC: &[u8] = b"Rar!\x1A\x07\x01\x00";
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+const ZIP_MAGIC: &[&[u8]] = &[b"PK\x03\x04", b"PK\x05\x06", b"PK\x07\x08"];
+
+#[derive(Clone, Copy, Debug)]
+enum Format {
+  Folder,
+  Rar,
+  SevenZip,
+  Tar,
+  TarGz,
+  Zip,
+}
+
+impl Format {
+  /// Detect the format by its magic bytes, as the extension may not match the actual content.
+  fn detect(path: &Path) -> Result<Self> {
+    if path.is_dir() {
+      return Ok(Self::Folder);
+    }
+
+    // The first tar header block is more than enough to identify every format.
+    let mut magic = Vec::with_capacity(512);
+    File::open(path)?
+      .take(512)
+      .read_to_end(&mut magic)?;
+
+    if magic.starts_with(RAR4_MAGIC) || magic.starts_with(RAR5_MAGIC) {
+      Ok(Self::Rar)
+    } else if magic.starts_with(SEVEN_ZIP_MAGIC) {
+      Ok(Self::SevenZip)
+    } else if magic.starts_with(GZIP_MAGIC) && is_tar_gz(path)? {
+      Ok(Self::TarGz)
+    } else if is_tar(&magic) {
+      Ok(Self::Tar)
+    } else if ZIP_MAGIC.iter().any(|it| magic.starts_with(it)) {
+      Ok(Self::Zip)
+    } else {
+      Err(err!(InvalidBook, "unsupported format: {}", path.display()))
+    }
+  }
+}
+
+fn is_tar(header: &[u8]) -> bool {
+  header
+    .get(TAR_MAGIC_OFFSET..)
+    .is_some_and(|it| it.starts_with(TAR_MAGIC))
+}
+
+/// Gzip can compress anything, so only the decompressed header tells if it's a tar archive.
+fn is_tar_gz(path: &Path) -> Result<bool> {
+  let mut header = Vec::with_capacity(512);
+  let decoder = GzDecoder::new(File::open(path)?);
+  if decoder
+    .take(512)
+    .read_to_end(&mut header)
+    .is_err()
+  {
+    return Ok(false);
+  }
+
+  Ok(is_tar(&header))
+}
+
+pub(super) fn open(path: &Path) -> Result<Box<dyn BookArchive>> {
+  let format = Format::detect(path)?;
+
+  #[cfg(feature = "tracing")]
+  trace!(?format);
+
+  let archive: Box<dyn BookArchive> = match format {
+    Format::Folder => Box::new(ImageFolder::open(path)?),
+    Format::Rar => Box::new(Cbr::open(path)?),
+    Format::SevenZip => Box::new(Cb7::open(path)?),
+    Format::Tar => Box::new(Cbt::open(path, false)?),
+    Format::TarGz => Box::new(Cbt::open(path, true)?),
+    Format::Zip => Box::new(Cbz::open(path)?),
+  };
+
+  Ok(archive)
+}
+
+/// Write a new archive to a temporary file in the same directory,
+/// replacing the original file once `f` succeeds.
+///
+/// The new archive is flushed to disk and opened again to check it has as many `pages`
+/// as expected before being renamed over the original, so the book is never left half written.
+/// If `backup` is `true`, the original is kept next to it with a `.bak` extension.
+fn replace<F>(path: &Path, pages: usize, backup: bool, f: F) -> Result<()>
+where
+  F: FnOnce(&mut File) -> Result<()>,
+{
+  let parent = path.try_parent()?;
+  let mut temp = Tempfile::new_in(parent)?;
+  f(&mut temp.file)?;
+  temp.file.sync_all()?;
+
+  verify(&temp.path, pages)?;
+
+  if backup {
+    keep_backup(path)?;
+  }
+
+  // Both are in the same directory, so the rename is atomic.
+  fs::rename(&temp.path, path)?;
+  sync_dir(parent)?;
+
+  Ok(())
+}
+
+/// Open the archive again, failing if it doesn't have the expected number of pages.
+fn verify(path: &Path, expected: usize) -> Result<()> {
+  let archive = open(path)?;
+  let pages = book_pages(archive.file_names()).len();
+  if pages != expected {
+    bail!(
+      InvalidBook,
+      "expected {expected} pages after rewriting, found {pages}"
+    );
+  }
+
+  Ok(())
+}
+
+/// Link the original file to `<name>.bak`, copying it if links aren't supported.
+/// As the original is replaced by a rename, the link keeps pointing to its old content.
+fn keep_backup(path: &Path) -> Result<()> {
+  let mut bak = path.as_os_str().to_owned();
+  bak.push(".bak");
+  let bak = PathBuf::from(bak);
+
+  if bak.try_exists()? {
+    fs::remove_file(&bak)?;
+  }
+
+  if fs::hard_link(path, &bak).is_err() {
+    fs::copy(path, &bak)?;
+  }
+
+  Ok(())
+}
+
+/// The rename is only durable once the directory entry itself is flushed to disk.
+/// Windows doesn't allow opening directories as files, but it also doesn't need it.
+fn sync_dir(dir: &Path) -> Result<()> {
+  if cfg!(unix) {
+    File::open(dir)?.sync_all()?;
+  }
+
+  Ok(())
+}
+
+/// Number of pages the archive will have once rewritten without the files in `skip`
+/// and with those in `files`.
+fn count_pages(names: Vec<String>, skip: &[&str], files: &[(&str, &[u8])]) -> usize {
+  let names = names
+    .into_iter()
+    .filter(|name| !skip.contains(&name.as_str()))
+    .chain(files.iter().map(|(name, _)| (*name).to_owned()));
+
+  book_pages(names).len()
+}
+
+/// Filter the page names, sorting them in natural order.
+pub(super) fn book_pages<I>(names: I) -> PageMap
+where
+  I: IntoIterator<Item = String>,
+{
+  let globset = glob::book_page();
+  names
+    .into_iter()
+    .filter(|name| globset.is_match(name))
+    .sorted_unstable_by(|a, b| compare_ignore_case(a, b))
+    .enumerate()
+    .collect()
+}
@@ -0,0 +1,192 @@
+use super::file::BookFile;
+use crate::prelude::*;
+use ahash::{HashMap, HashMapExt};
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Most books kept open at once, as each one holds a file descriptor.
+const CAPACITY: usize = 32;
+/// Rough limit on the memory taken by the central directories and page lists of open books.
+const MAX_MEMORY: usize = 64 * 1024 * 1024;
+/// Books left unused for this long are closed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Open books, the least recently used being closed first whenever there are too many of them.
+pub(super) struct BookCache {
+  entries: HashMap<PathBuf, Entry>,
+  memory: usize,
+  hits: u64,
+  misses: u64,
+  evicted: Evicted,
+}
+
+struct Entry {
+  book: BookFile,
+  last_used: Instant,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evicted {
+  /// Closed to make room for another book.
+  pub capacity: u64,
+  /// Closed because the open books were taking too much memory.
+  pub memory: u64,
+  /// Closed after being left unused for too long.
+  pub idle: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Reason {
+  Capacity,
+  Memory,
+  Idle,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookCacheStats {
+  pub len: usize,
+  pub capacity: usize,
+  /// Estimated, in bytes.
+  pub memory: usize,
+  pub max_memory: usize,
+  pub idle_timeout_secs: u64,
+  pub hits: u64,
+  pub misses: u64,
+  pub evicted: Evicted,
+}
+
+impl BookCache {
+  pub(super) fn new() -> Self {
+    Self {
+      entries: HashMap::with_capacity(CAPACITY),
+      memory: 0,
+      hits: 0,
+      misses: 0,
+      evicted: Evicted::default(),
+    }
+  }
+
+  /// Get the book from the cache, opening it if needed.
+  pub(super) fn get(&mut self, path: &Path) -> Result<&mut BookFile> {
+    if self.entries.contains_key(path) {
+      self.hits += 1;
+    } else {
+      self.misses += 1;
+      let book = BookFile::open(path)?;
+      self.insert(path, book);
+    }
+
+    let entry = self
+      .entries
+      .get_mut(path)
+      .expect("book should be in the cache");
+
+    entry.last_used = Instant::now();
+    Ok(&mut entry.book)
+  }
+
+  pub(super) fn remove(&mut self, path: &Path) -> Option<BookFile> {
+    let entry = self.entries.remove(path)?;
+    self.memory = self
+      .memory
+      .saturating_sub(entry.book.estimated_size());
+
+    Some(entry.book)
+  }
+
+  /// Close every book that has been left unused for too long.
+  pub(super) fn evict_idle(&mut self) {
+    let now = Instant::now();
+    let idle = self
+      .entries
+      .iter()
+      .filter(|(_, it)| it.last_used + IDLE_TIMEOUT <= now)
+      .map(|(path, _)| path.clone())
+      .collect_vec();
+
+    for path in idle {
+      self.evict(&path, Reason::Idle);
+    }
+  }
+
+  /// When the next book will have been left unused for too long, if there's any open.
+  pub(super) fn idle_deadline(&self) -> Option<Instant> {
+    self
+      .entries
+      .values()
+      .map(|it| it.last_used + IDLE_TIMEOUT)
+      .min()
+  }
+
+  pub(super) fn stats(&self) -> BookCacheStats {
+    BookCacheStats {
+      len: self.entries.len(),
+      capacity: CAPACITY,
+      memory: self.memory,
+      max_memory: MAX_MEMORY,
+      idle_timeout_secs: IDLE_TIMEOUT.as_secs(),
+      hits: self.hits,
+      misses: self.misses,
+      evicted: self.evicted,
+    }
+  }
+
+  /// Make room for the book before inserting it, so that it's never the one evicted.
+  /// A book too large to fit on its own is still kept, as long as it's the only one.
+  fn insert(&mut self, path: &Path, book: BookFile) {
+    let size = book.estimated_size();
+    loop {
+      let reason = if self.entries.len() >= CAPACITY {
+        Reason::Capacity
+      } else if self.memory + size > MAX_MEMORY {
+        Reason::Memory
+      } else {
+        break;
+      };
+
+      let lru = self
+        .entries
+        .iter()
+        .min_by_key(|(_, it)| it.last_used)
+        .map(|(path, _)| path.clone());
+
+      match lru {
+        Some(lru) => self.evict(&lru, reason),
+        None => break,
+      }
+    }
+
+    self.memory += size;
+    let last_used = Instant::now();
+    self
+      .entries
+      .insert(path.to_path_buf(), Entry { book, last_used });
+  }
+
+  fn evict(&mut self, path: &Path, reason: Reason) {
+    if self.remove(path).is_none() {
+      return;
+    }
+
+    match reason {
+      Reason::Capacity => self.evicted.capacity += 1,
+      Reason::Memory => self.evicted.memory += 1,
+      Reason::Idle => self.evicted.idle += 1,
+    }
+
+    #[cfg(feature = "tracing")]
+    debug!(book_cache_evict = %path.display(), ?reason, stats = ?self.stats());
+  }
+}
+
+impl fmt::Debug for BookCache {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("BookCache")
+      .field("len", &self.entries.len())
+      .field("memory", &self.memory)
+      .finish_non_exhaustive()
+  }
+}
@@ -1,26 +1,27 @@
+use super::archive::{self, BookArchive};
 use super::PageMap;
+use crate::bail;
+use crate::book::comic_info::{ComicInfo, ComicInfoPatch};
 use crate::book::metadata::Metadata;
-use crate::fs::Tempfile;
 use crate::prelude::*;
-use crate::utils::glob;
-use natord::compare_ignore_case;
 use std::fmt;
-use std::fs::{self, File};
-use std::io::{Read, Seek, Write};
 use std::sync::Arc;
-use zip::result::{ZipError, ZipResult};
-use zip::write::SimpleFileOptions as ZipSimpleFileOptions;
-use zip::{ZipArchive, ZipWriter};
 
 #[cfg(feature = "devtools")]
-const METADATA_FILENAME: &str = "kotori-dev.json";
+pub(super) const METADATA_FILENAME: &str = "kotori-dev.json";
 #[cfg(not(feature = "devtools"))]
-const METADATA_FILENAME: &str = "kotori.json";
+pub(super) const METADATA_FILENAME: &str = "kotori.json";
+
+const COMIC_INFO_FILENAME: &str = "ComicInfo.xml";
+
+/// Rough number of bytes taken by each file in the archive index, besides its name.
+const FILE_ENTRY_OVERHEAD: usize = 256;
 
 pub(super) struct BookFile {
-  file: ZipArchive<File>,
+  archive: Box<dyn BookArchive>,
   path: PathBuf,
   pub(super) pages: Arc<PageMap>,
+  size: usize,
 }
 
 impl BookFile {
@@ -30,15 +31,22 @@ impl BookFile {
     let start = Instant::now();
 
     let path = path.to_owned();
-    let reader = File::open(&path)?;
+    let archive = archive::open(&path)?;
+    let names = archive.file_names();
+
+    // Names are held by both the archive index and the list of pages.
+    let size = names
+      .iter()
+      .map(|it| it.len() * 2 + FILE_ENTRY_OVERHEAD)
+      .sum();
 
-    let zip = ZipArchive::new(reader)?;
-    let pages = zip.book_pages();
+    let pages = archive::book_pages(names);
 
     let file = BookFile {
-      file: zip,
+      archive,
       pages: Arc::new(pages),
       path,
+      size,
     };
 
     #[cfg(feature = "tracing")]
@@ -47,8 +55,16 @@ impl BookFile {
     Ok(file)
   }
 
+  /// Estimate of the memory held while the book is open.
+  pub(super) fn estimated_size(&self) -> usize {
+    self.size
+  }
+
   pub(super) fn read_page(&mut self, page: &str) -> Result<Vec<u8>> {
-    self.file.read_file(page).map_err(Into::into)
+    self
+      .archive
+      .read_file(page)?
+      .ok_or_else(|| err!(PageNotFound, "{page}"))
   }
 
   #[cfg_attr(feature = "tracing", instrument)]
@@ -57,8 +73,8 @@ impl BookFile {
     let start = Instant::now();
 
     let metadata = self
-      .file
-      .read_book_metadata()?
+      .archive
+      .read_file(METADATA_FILENAME)?
       .as_deref()
       .map(serde_json::from_slice)
       .transpose()?;
@@ -75,21 +91,48 @@ impl BookFile {
   }
 
   #[cfg_attr(feature = "tracing", instrument)]
-  pub(super) fn delete_page(mut self, page: &str) -> Result<()> {
+  pub(super) fn read_comic_info(&mut self) -> Result<Option<ComicInfo>> {
     #[cfg(feature = "tracing")]
     let start = Instant::now();
 
-    let parent = self.path.try_parent()?;
-    let mut temp = Tempfile::new_in(parent)?;
-    let mut writer = ZipWriter::new(&mut temp.file);
+    let Some(name) = self.comic_info_name() else {
+      return Ok(None);
+    };
 
+    let comic_info = self
+      .archive
+      .read_file(&name)?
+      .as_deref()
+      .map(ComicInfo::from_xml)
+      .transpose()?;
+
+    #[cfg(feature = "tracing")]
+    {
+      info!("comic info read in {:?}", start.elapsed());
+      if let Some(comic_info) = &comic_info {
+        trace!(?comic_info);
+      }
+    }
+
+    Ok(comic_info)
+  }
+
+  /// Name of the `ComicInfo.xml` file in the archive, if any.
+  fn comic_info_name(&self) -> Option<String> {
+    // Not every tool agrees on the casing of the file name.
     self
-      .file
-      .raw_copy_if(&mut writer, |it| *it != page)?;
+      .archive
+      .file_names()
+      .into_iter()
+      .find(|it| it.eq_ignore_ascii_case(COMIC_INFO_FILENAME))
+  }
 
-    writer.finish()?;
-    fs::remove_file(&self.path)?;
-    fs::rename(&temp.path, self.path)?;
+  #[cfg_attr(feature = "tracing", instrument)]
+  pub(super) fn delete_page(self, page: &str, backup: bool) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    let start = Instant::now();
+
+    self.archive.delete_file(page, backup)?;
 
     #[cfg(feature = "tracing")]
     info!("page deleted in {:?}", start.elapsed());
@@ -97,6 +140,56 @@ impl BookFile {
     Ok(())
   }
 
+  /// Put a deleted page back in the book.
+  /// Pages are sorted by name, so it takes the same position it had before being deleted.
+  #[cfg_attr(feature = "tracing", instrument(skip(bytes)))]
+  pub(super) fn restore_page(self, page: &str, bytes: &[u8], backup: bool) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    let start = Instant::now();
+
+    if self.pages.values().any(|it| it == page) {
+      bail!(PageAlreadyExists, "{page}");
+    }
+
+    self
+      .archive
+      .write_files(&[(page, bytes)], backup)?;
+
+    #[cfg(feature = "tracing")]
+    info!("page restored in {:?}", start.elapsed());
+
+    Ok(())
+  }
+
+  /// Fingerprint of the book, computed from the name and size of each page.
+  ///
+  /// It doesn't change when the file is renamed or moved, nor when only the metadata is written,
+  /// and reading the whole content of every page is avoided.
+  #[cfg_attr(feature = "tracing", instrument)]
+  pub(super) fn hash(&mut self) -> Result<String> {
+    #[cfg(feature = "tracing")]
+    let start = Instant::now();
+
+    let mut hasher = blake3::Hasher::new();
+    for page in self.pages.values() {
+      let size = self
+        .archive
+        .file_size(page)?
+        .ok_or_else(|| err!(PageNotFound, "{page}"))?;
+
+      hasher.update(page.as_bytes());
+      hasher.update(&[0]);
+      hasher.update(&size.to_le_bytes());
+    }
+
+    let hash = hasher.finalize().to_hex().to_string();
+
+    #[cfg(feature = "tracing")]
+    info!("book hashed in {:?}", start.elapsed());
+
+    Ok(hash)
+  }
+
   pub(super) fn first_page_name(&self) -> Result<String> {
     self
       .pages
@@ -107,105 +200,102 @@ impl BookFile {
   }
 
   #[cfg_attr(feature = "tracing", instrument)]
-  pub(super) fn write_metadata(mut self, metadata: &Metadata) -> Result<()> {
+  pub(super) fn write_metadata(
+    mut self,
+    metadata: &Metadata,
+    comic_info: bool,
+    backup: bool,
+  ) -> Result<()> {
     #[cfg(feature = "tracing")]
     let start = Instant::now();
 
-    let parent = self.path.try_parent()?;
-    let mut temp = Tempfile::new_in(parent)?;
-    let mut writer = ZipWriter::new(&mut temp.file);
-
-    self
-      .file
-      .raw_copy_if(&mut writer, |it| *it != METADATA_FILENAME)?;
-
-    writer.start_file(METADATA_FILENAME, ZipSimpleFileOptions::default())?;
-    serde_json::to_writer_pretty(&mut writer, &metadata)?;
-
-    writer.finish()?;
-    fs::remove_file(&self.path)?;
-    fs::rename(&temp.path, self.path)?;
+    let bytes = serde_json::to_vec_pretty(metadata)?;
+    if comic_info {
+      let (name, xml) = self.patch_comic_info(metadata)?;
+      let files = [
+        (METADATA_FILENAME, bytes.as_slice()),
+        (name.as_str(), xml.as_slice()),
+      ];
+      self.archive.write_files(&files, backup)?;
+    } else {
+      let files = [(METADATA_FILENAME, bytes.as_slice())];
+      self.archive.write_files(&files, backup)?;
+    }
 
     #[cfg(feature = "tracing")]
     info!("metadata written in {:?}", start.elapsed());
 
     Ok(())
   }
-}
 
-impl fmt::Debug for BookFile {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    f.debug_struct("BookFile")
-      .field("path", &self.path)
-      .field("pages", &self.pages.len())
-      .finish_non_exhaustive()
-  }
-}
+  /// Update the existing `ComicInfo.xml` with the metadata, or create a new one.
+  /// Returns the name under which it should be written, along with its content.
+  fn patch_comic_info(&mut self, metadata: &Metadata) -> Result<(String, Vec<u8>)> {
+    let name = self
+      .comic_info_name()
+      .unwrap_or_else(|| COMIC_INFO_FILENAME.to_owned());
 
-trait ZipArchiveExt {
-  fn book_pages(&self) -> PageMap;
+    let existing = self.archive.read_file(&name)?;
 
-  fn file_names_by<F>(&mut self, f: F) -> Vec<String>
-  where
-    F: FnMut(&&str) -> bool;
+    let title = metadata
+      .title
+      .as_ref()
+      .map(|it| it.as_str().to_owned());
+    let rating = metadata
+      .rating
+      .filter(|it| *it > 0)
+      .map(|it| it.to_string());
 
-  fn raw_copy_if<W, F>(&mut self, writer: &mut ZipWriter<&mut W>, f: F) -> ZipResult<()>
-  where
-    W: Write + Seek,
-    F: FnMut(&&str) -> bool;
+    let mut patch = ComicInfoPatch::default().field("CommunityRating", rating);
 
-  fn read_book_metadata(&mut self) -> ZipResult<Option<Vec<u8>>>;
-  fn read_file(&mut self, name: &str) -> ZipResult<Vec<u8>>;
-}
-
-impl<T> ZipArchiveExt for ZipArchive<T>
-where
-  T: Read + Seek,
-{
-  fn book_pages(&self) -> PageMap {
-    let globset = glob::book_page();
-    self
-      .file_names()
-      .filter(|name| globset.is_match(name))
-      .sorted_unstable_by(|a, b| compare_ignore_case(a, b))
-      .enumerate()
-      .map(|(idx, name)| (idx, name.to_owned()))
-      .collect()
-  }
+    if let Some(title) = title {
+      patch = patch.field("Title", Some(title));
+    }
 
-  fn file_names_by<F>(&mut self, f: F) -> Vec<String>
-  where
-    F: FnMut(&&str) -> bool,
-  {
-    self.file_names().filter(f).map_into().collect()
-  }
+    // Tags are read from both `Genre` and `Tags`, so those that came from `Genre` are kept there.
+    // Otherwise, a tag removed in Kotori would come back the next time the book is saved.
+    if let Some(tags) = &metadata.tags {
+      let genres = existing
+        .as_deref()
+        .and_then(|it| ComicInfo::from_xml(it).ok())
+        .map(|it| it.genre_names())
+        .unwrap_or_default();
+
+      let (genre, tags): (Vec<_>, Vec<_>) = tags.iter().map(String::as_str).partition(|tag| {
+        genres
+          .iter()
+          .any(|it| it.eq_ignore_ascii_case(tag))
+      });
+
+      let join = |names: Vec<&str>| Some(names.join(", ")).filter(|it| !it.is_empty());
+      patch = patch
+        .field("Genre", join(genre))
+        .field("Tags", join(tags));
+    }
 
-  fn read_file(&mut self, name: &str) -> ZipResult<Vec<u8>> {
-    let mut file = self.by_name(name)?;
-    let size = usize::try_from(file.size()).unwrap_or_default();
-    let mut buf = Vec::with_capacity(size);
-    file.read_to_end(&mut buf)?;
-    Ok(buf)
-  }
+    let cover = metadata.cover.as_deref().and_then(|cover| {
+      self
+        .pages
+        .iter()
+        .find(|(_, page)| page.as_str() == cover)
+        .map(|(index, _)| *index)
+    });
 
-  fn read_book_metadata(&mut self) -> ZipResult<Option<Vec<u8>>> {
-    match self.read_file(METADATA_FILENAME) {
-      Ok(it) => Ok(Some(it)),
-      Err(ZipError::FileNotFound) => Ok(None),
-      Err(err) => Err(err),
+    if let Some(cover) = cover {
+      patch = patch.front_cover(cover);
     }
-  }
 
-  fn raw_copy_if<W, F>(&mut self, writer: &mut ZipWriter<&mut W>, f: F) -> ZipResult<()>
-  where
-    W: Write + Seek,
-    F: FnMut(&&str) -> bool,
-  {
-    for name in self.file_names_by(f) {
-      let file = self.by_name(&name)?;
-      writer.raw_copy_file(file)?;
-    }
+    let xml = patch.apply(existing.as_deref())?;
 
-    Ok(())
+    Ok((name, xml))
+  }
+}
+
+impl fmt::Debug for BookFile {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("BookFile")
+      .field("path", &self.path)
+      .field("pages", &self.pages.len())
+      .finish_non_exhaustive()
   }
 }
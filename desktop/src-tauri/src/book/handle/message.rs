@@ -1,4 +1,7 @@
+use super::cache::BookCacheStats;
+use super::sidecar::MetadataStorage;
 use super::PageMap;
+use crate::book::comic_info::ComicInfo;
 use crate::book::metadata::Metadata;
 use crate::prelude::*;
 use crate::result::TxResult;
@@ -17,14 +20,30 @@ pub(super) enum Message {
   DeletePage {
     path: PathBuf,
     page: String,
+    backup: bool,
     tx: TxResult<()>,
   },
+  Flush {
+    nt: Arc<Notify>,
+  },
+  GetCacheStats {
+    tx: TxResult<BookCacheStats>,
+  },
+  GetComicInfo {
+    path: PathBuf,
+    tx: TxResult<Option<ComicInfo>>,
+  },
   GetFirstPageName {
     path: PathBuf,
     tx: TxResult<String>,
   },
+  GetHash {
+    path: PathBuf,
+    tx: TxResult<String>,
+  },
   GetMetadata {
     path: PathBuf,
+    storage: MetadataStorage,
     tx: TxResult<Option<Metadata>>,
   },
   GetPages {
@@ -36,9 +55,19 @@ pub(super) enum Message {
     page: String,
     tx: TxResult<Vec<u8>>,
   },
+  RestorePage {
+    path: PathBuf,
+    page: String,
+    bytes: Vec<u8>,
+    backup: bool,
+    tx: TxResult<()>,
+  },
   SetMetadata {
     path: PathBuf,
     metadata: Metadata,
+    comic_info: bool,
+    backup: bool,
+    storage: MetadataStorage,
     tx: TxResult<()>,
   },
 }
@@ -1,13 +1,19 @@
 mod actor;
+mod archive;
+mod cache;
 mod file;
 mod message;
+mod sidecar;
 
+use crate::book::comic_info::ComicInfo;
 use crate::book::metadata::Metadata;
 use crate::prelude::*;
 use crate::utils::collections::OrderedMap;
-use crate::{send_notify, send_tx};
+use crate::{bail, send_notify, send_tx};
 use actor::Actor;
+pub use cache::BookCacheStats;
 use message::Message;
+pub use sidecar::MetadataStorage;
 use std::sync::{mpsc, Arc};
 use std::{fmt, thread};
 
@@ -15,20 +21,27 @@ pub(super) type PageMap = OrderedMap<usize, String>;
 
 #[derive(Clone)]
 pub struct BookHandle {
+  app: AppHandle,
   sender: mpsc::Sender<Message>,
 }
 
 impl BookHandle {
-  pub fn new() -> Self {
+  const BACKUP_KEY: &str = "keepBookBackup";
+
+  pub fn new(app: &AppHandle) -> Result<Self> {
     let (sender, receiver) = mpsc::channel();
-    let mut actor = Actor::new(receiver);
+    let sidecar_dir = app.path().sidecar_dir()?;
+    let mut actor = Actor::new(app.clone(), receiver, sidecar_dir);
 
     thread::spawn(move || actor.run());
 
-    Self { sender }
+    Ok(Self { app: app.clone(), sender })
   }
 
   /// Close the book file, removing it from the cache.
+  ///
+  /// Books are also closed on their own once left unused for a while,
+  /// or when too many of them are open.
   pub async fn close(&self, path: &Path) {
     let path = path.to_owned();
     send_notify!(self, Close { path });
@@ -45,15 +58,35 @@ impl BookHandle {
     send_tx!(self, ReadPage { path, page })
   }
 
-  pub async fn delete_page(&self, path: &Path, page: &str) -> Result<()> {
+  /// Rewrite the book without the page, keeping the original as a `.bak` if `backup` is `true`.
+  /// Books are never modified while their metadata is stored in a sidecar.
+  pub async fn delete_page(&self, path: &Path, page: &str, backup: bool) -> Result<()> {
+    self.ensure_writable(path)?;
     let path = path.to_owned();
     let page = page.to_owned();
-    send_tx!(self, DeletePage { path, page })
+    send_tx!(self, DeletePage { path, page, backup })
   }
 
+  /// Put a deleted page back in the book, keeping the original as a `.bak` if `backup` is `true`.
+  pub async fn restore_page(
+    &self,
+    path: &Path,
+    page: &str,
+    bytes: Vec<u8>,
+    backup: bool,
+  ) -> Result<()> {
+    self.ensure_writable(path)?;
+    let path = path.to_owned();
+    let page = page.to_owned();
+    send_tx!(self, RestorePage { path, page, bytes, backup })
+  }
+
+  /// Read the metadata from wherever [`MetadataStorage`] says it's stored.
+  /// Books whose sidecar doesn't exist yet fall back to the metadata inside them, if any.
   pub async fn get_metadata(&self, path: &Path) -> Result<Option<Metadata>> {
     let path = path.to_owned();
-    let metadata = send_tx!(self, GetMetadata { path })?;
+    let storage = MetadataStorage::get(&self.app)?;
+    let metadata = send_tx!(self, GetMetadata { path, storage })?;
 
     #[cfg(feature = "tracing")]
     if let Some(metadata) = &metadata {
@@ -63,18 +96,97 @@ impl BookHandle {
     Ok(metadata)
   }
 
-  pub async fn set_metadata(&self, path: &Path, metadata: Metadata) -> Result<()> {
+  pub async fn get_comic_info(&self, path: &Path) -> Result<Option<ComicInfo>> {
+    let path = path.to_owned();
+    send_tx!(self, GetComicInfo { path })
+  }
+
+  /// Write the metadata to the book, along with a `ComicInfo.xml` if `comic_info` is `true`.
+  /// The original is kept as a `.bak` if `backup` is `true`.
+  ///
+  /// If [`MetadataStorage`] says the metadata is stored in a sidecar, only that file is written
+  /// and both `comic_info` and `backup` are ignored.
+  ///
+  /// Writing is delayed a little, so that rapid changes to the same book cost a single write.
+  /// This returns once the write is queued, and errors from the write itself are only logged.
+  pub async fn set_metadata(
+    &self,
+    path: &Path,
+    metadata: Metadata,
+    comic_info: bool,
+    backup: bool,
+  ) -> Result<()> {
     #[cfg(feature = "tracing")]
-    trace!(set_metadata = ?metadata);
+    trace!(set_metadata = ?metadata, comic_info, backup);
+
+    let path = path.to_owned();
+    let storage = MetadataStorage::get(&self.app)?;
+    send_tx!(
+      self,
+      SetMetadata {
+        path,
+        metadata,
+        comic_info,
+        backup,
+        storage
+      }
+    )
+  }
+
+  /// Write any metadata still waiting to be written.
+  pub async fn flush(&self) {
+    send_notify!(self, Flush {});
+  }
 
+  /// How the cache of open books is doing.
+  pub async fn cache_stats(&self) -> Result<BookCacheStats> {
+    send_tx!(self, GetCacheStats {})
+  }
+
+  /// Fingerprint of the book, which stays the same wherever the file is moved to.
+  pub async fn get_hash(&self, path: &Path) -> Result<String> {
     let path = path.to_owned();
-    send_tx!(self, SetMetadata { path, metadata })
+    send_tx!(self, GetHash { path })
   }
 
   pub async fn get_first_page_name(&self, path: &Path) -> Result<String> {
     let path = path.to_owned();
     send_tx!(self, GetFirstPageName { path })
   }
+
+  fn ensure_writable(&self, path: &Path) -> Result<()> {
+    if MetadataStorage::get(&self.app)?.is_sidecar() {
+      bail!(ReadOnlyBook, "{}", path.display());
+    }
+
+    Ok(())
+  }
+
+  /// Whether the original file should be kept as a `.bak` whenever a book is rewritten.
+  pub fn is_backup_enabled(app: &AppHandle) -> Result<bool> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        let enabled = store
+          .get(Self::BACKUP_KEY)
+          .and_then(|it| it.as_bool())
+          .unwrap_or(false);
+
+        Ok(enabled)
+      })
+      .map_err(Into::into)
+  }
+
+  pub fn set_backup_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        store.set(Self::BACKUP_KEY, enabled.into())
+      })
+      .map_err(Into::into)
+  }
 }
 
 impl fmt::Debug for BookHandle {
@@ -0,0 +1,97 @@
+use super::file::METADATA_FILENAME;
+use crate::book::metadata::Metadata;
+use crate::fs::Tempfile;
+use crate::prelude::*;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use strum::{Display, EnumString};
+
+/// Where the metadata of a book is kept.
+///
+/// Books on read-only media, or shared with other readers, can have their metadata
+/// stored in a sidecar file instead, so that Kotori never modifies them.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "camelCase")]
+pub enum MetadataStorage {
+  /// Inside the book itself.
+  #[default]
+  Archive,
+  /// In a file next to the book, such as `Book.cbz.kotori.json`.
+  NextToBook,
+  /// In the app data directory, under the hash of the book.
+  AppData,
+}
+
+impl MetadataStorage {
+  const KEY: &str = "metadataStorage";
+
+  pub fn get(app: &AppHandle) -> Result<Self> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        let storage = store
+          .get(Self::KEY)
+          .and_then(|it| it.as_str())
+          .and_then(|it| MetadataStorage::try_from(it).ok())
+          .unwrap_or_default();
+
+        Ok(storage)
+      })
+      .map_err(Into::into)
+  }
+
+  pub fn set(self, app: &AppHandle) -> Result<()> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        let storage = self.to_string();
+        store.set(Self::KEY, storage.into())
+      })
+      .map_err(Into::into)
+  }
+
+  /// Whether the books are left untouched.
+  pub fn is_sidecar(self) -> bool {
+    self != Self::Archive
+  }
+}
+
+/// Sidecar file next to the book.
+pub(super) fn next_to(book: &Path) -> PathBuf {
+  let mut path = book.as_os_str().to_owned();
+  path.push(".");
+  path.push(METADATA_FILENAME);
+  PathBuf::from(path)
+}
+
+/// Sidecar file in the app data directory.
+pub(super) fn in_dir(dir: &Path, hash: &str) -> PathBuf {
+  dir.join(format!("{hash}.json"))
+}
+
+pub(super) fn read(path: &Path) -> Result<Option<Metadata>> {
+  match fs::read(path) {
+    Ok(bytes) => serde_json::from_slice(&bytes)
+      .map(Some)
+      .map_err(Into::into),
+    Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+    Err(err) => Err(err.into()),
+  }
+}
+
+/// Write the metadata to a temporary file before renaming it over the sidecar,
+/// so that other readers never see it half written.
+pub(super) fn write(path: &Path, metadata: &Metadata) -> Result<()> {
+  let parent = path.try_parent()?;
+  fs::create_dir_all(parent)?;
+
+  let bytes = serde_json::to_vec_pretty(metadata)?;
+  let mut temp = Tempfile::new_in(parent)?;
+  temp.file.write_all(&bytes)?;
+  temp.file.sync_all()?;
+  fs::rename(&temp.path, path)?;
+
+  Ok(())
+}
@@ -12,6 +12,8 @@ pub struct Metadata {
   pub cover: Option<String>,
   pub rating: Option<u8>,
   pub read: Option<bool>,
+  pub progress: Option<Progress>,
+  pub tags: Option<Vec<String>>,
 
   /// Kotori version.
   pub version: Option<Version>,
@@ -23,19 +25,33 @@ impl Metadata {
   }
 }
 
+/// Where the reader was left off.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Progress {
+  pub page_index: usize,
+  pub page_name: String,
+}
+
 impl TryFrom<&Book> for Metadata {
   type Error = crate::error::Error;
 
   fn try_from(book: &Book) -> Result<Self> {
     let title = Title::new(&book.title);
     let rating = u8::try_from(book.rating)?;
-    
-    let metadata = Builder::new(&book.path)
+
+    let mut builder = Builder::new(&book.path)
       .title(title)
       .cover(&book.cover)
       .rating(rating)
-      .read(book.read)
-      .build();
+      .read(book.read);
+
+    if let Some(index) = book.last_page_index
+      && let Some(name) = &book.last_page_name
+    {
+      builder = builder.progress(usize::try_from(index)?, name);
+    }
+
+    let metadata = builder.build();
 
     Ok(metadata)
   }
@@ -47,6 +63,7 @@ pub struct Builder {
   cover: Option<String>,
   rating: u8,
   read: bool,
+  progress: Option<Progress>,
 }
 
 impl Builder {
@@ -59,6 +76,7 @@ impl Builder {
       rating: 0,
       cover: None,
       read: false,
+      progress: None,
     }
   }
 
@@ -73,6 +91,12 @@ impl Builder {
     self
   }
 
+  pub fn progress(mut self, page_index: usize, page_name: impl AsRef<str>) -> Self {
+    let page_name = page_name.as_ref().to_owned();
+    self.progress = Some(Progress { page_index, page_name });
+    self
+  }
+
   pub fn read(mut self, read: bool) -> Self {
     self.read = read;
     self
@@ -90,6 +114,8 @@ impl Builder {
       cover: self.cover,
       rating: Some(self.rating),
       read: Some(self.read),
+      progress: self.progress,
+      tags: None,
       version: Some(version),
     }
   }
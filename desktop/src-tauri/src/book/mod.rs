@@ -1,15 +1,19 @@
 mod active;
+mod comic_info;
 mod cover;
 mod handle;
 mod metadata;
+mod series;
 mod structs;
 mod title;
 
 use crate::prelude::*;
 use crate::reader;
 pub use active::ActiveBook;
-pub use handle::BookHandle;
-pub use metadata::Metadata;
+pub use comic_info::{ComicInfo, Manga};
+pub use handle::{BookCacheStats, BookHandle, MetadataStorage};
+pub use metadata::{Metadata, Progress};
+pub use series::{SeriesInfo, SeriesParser};
 pub use structs::{LibraryBook, ReaderBook};
 use tauri_plugin_dialog::{DialogExt, FileDialogBuilder};
 pub use title::Title;
@@ -20,7 +24,7 @@ pub async fn open_with_dialog(app: &AppHandle) -> Result<()> {
   let dialog = app.dialog().clone();
 
   FileDialogBuilder::new(dialog)
-    .add_filter("Book", &["cbr", "cbz", "zip"])
+    .add_filter("Book", &["cb7", "cbr", "cbt", "cbz", "zip"])
     .pick_files(move |response| {
       let _ = tx.send(response);
     });
@@ -0,0 +1,164 @@
+use super::Title;
+use crate::bail;
+use crate::database::model::Book;
+use crate::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::sync::LazyLock;
+
+/// Patterns used when the user hasn't configured any.
+/// They are tried in order, and the first one to match wins.
+const DEFAULT_PATTERNS: &[&str] = &[
+  // One Piece v01 c001, One Piece Vol. 1 Ch. 1
+  r"^(?P<series>.+?)\s+v(?:ol(?:ume)?)?\.?\s*(?P<volume>\d+)(?:\s+c(?:h(?:apter)?)?\.?\s*(?P<chapter>\d+(?:\.\d+)?))?\b",
+  // One Piece c001, One Piece Ch. 1
+  r"^(?P<series>.+?)\s+c(?:h(?:apter)?)?\.?\s*(?P<chapter>\d+(?:\.\d+)?)\b",
+  // Title - 012
+  r"^(?P<series>.+?)\s+-\s+(?P<chapter>\d+(?:\.\d+)?)\b",
+];
+
+/// Tags such as `[Group]` or `(Digital)` at either end of the title.
+static TAGS: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"^(?:\s*[\[(][^\])]*[\])])+\s*|\s*(?:[\[(][^\])]*[\])]\s*)+$").unwrap()
+});
+
+/// Where a book stands within its series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeriesInfo {
+  pub name: String,
+  pub volume: Option<i32>,
+  pub chapter: Option<f64>,
+}
+
+impl SeriesInfo {
+  /// Series, volume and chapter of the book, as told by its `ComicInfo.xml`.
+  pub fn from_model(book: &Book) -> Option<Self> {
+    let name = book.series.as_deref().map(str::trim)?;
+    if name.is_empty() {
+      return None;
+    }
+
+    let chapter = book
+      .number
+      .as_deref()
+      .and_then(|it| it.trim().parse().ok());
+
+    Some(Self {
+      name: name.to_owned(),
+      volume: book.volume,
+      chapter,
+    })
+  }
+}
+
+/// Tells the series of a book from its title.
+///
+/// Patterns are regular expressions with a `series` named group,
+/// and optionally a `volume` and a `chapter` one. They are always case insensitive.
+pub struct SeriesParser {
+  patterns: Vec<Regex>,
+}
+
+impl SeriesParser {
+  const PATTERNS_KEY: &str = "seriesPatterns";
+
+  pub fn new<I, S>(patterns: I) -> Result<Self>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    let patterns = patterns
+      .into_iter()
+      .map(|pattern| {
+        let pattern = pattern.as_ref();
+        let regex = RegexBuilder::new(pattern)
+          .case_insensitive(true)
+          .build()?;
+
+        if !regex
+          .capture_names()
+          .any(|it| it == Some("series"))
+        {
+          bail!(InvalidSeriesPattern, "{pattern}");
+        }
+
+        Ok(regex)
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    Ok(Self { patterns })
+  }
+
+  /// Parser with the patterns from the config, or the default ones if there are none.
+  pub fn from_config(app: &AppHandle) -> Result<Self> {
+    Self::new(Self::patterns(app)?)
+  }
+
+  pub fn patterns(app: &AppHandle) -> Result<Vec<String>> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    app
+      .with_store("config", |store| {
+        let patterns = store
+          .get(Self::PATTERNS_KEY)
+          .and_then(|it| serde_json::from_value::<Vec<String>>(it).ok())
+          .filter(|it| !it.is_empty())
+          .unwrap_or_else(|| {
+            DEFAULT_PATTERNS
+              .iter()
+              .map(ToString::to_string)
+              .collect()
+          });
+
+        Ok(patterns)
+      })
+      .map_err(Into::into)
+  }
+
+  /// Validate and save the patterns. An empty list brings back the default ones.
+  pub fn set_patterns(app: &AppHandle, patterns: Vec<String>) -> Result<()> {
+    use tauri_plugin_pinia::ManagerExt;
+
+    Self::new(&patterns)?;
+    let value = serde_json::to_value(patterns)?;
+    app
+      .with_store("config", |store| store.set(Self::PATTERNS_KEY, value))
+      .map_err(Into::into)
+  }
+
+  pub fn parse(&self, title: &Title) -> Option<SeriesInfo> {
+    let title = TAGS.replace_all(title.as_str(), "");
+    self.patterns.iter().find_map(|pattern| {
+      let captures = pattern.captures(&title)?;
+      let name = captures
+        .name("series")?
+        .as_str()
+        .trim()
+        .trim_end_matches(['-', '_', '.', ',', ' ']);
+
+      if name.is_empty() {
+        return None;
+      }
+
+      let volume = captures
+        .name("volume")
+        .and_then(|it| it.as_str().parse().ok());
+      let chapter = captures
+        .name("chapter")
+        .and_then(|it| it.as_str().parse().ok());
+
+      Some(SeriesInfo {
+        name: name.to_owned(),
+        volume,
+        chapter,
+      })
+    })
+  }
+
+  /// Series of the book, preferring what its `ComicInfo.xml` says over its file name.
+  pub fn parse_model(&self, book: &Book) -> Option<SeriesInfo> {
+    SeriesInfo::from_model(book).or_else(|| {
+      let title = Title::try_from(book.path.as_str()).ok()?;
+      self.parse(&title)
+    })
+  }
+}
@@ -12,6 +12,8 @@ pub struct ReaderBook {
   pub path: PathBuf,
   pub title: Title,
   pub pages: Vec<ReaderBookPage>,
+  /// Page where the reader was left off.
+  pub last_page: Option<usize>,
 }
 
 impl ReaderBook {
@@ -28,7 +30,9 @@ impl ReaderBook {
       .sorted_unstable_by_key(|it| it.index)
       .collect_vec();
 
-    Ok(Self { id, path, title, pages })
+    let last_page = book.last_page().await?;
+
+    Ok(Self { id, path, title, pages, last_page })
   }
 
   pub async fn from_reader(app: &AppHandle, window_id: u16) -> Result<Self> {
@@ -68,9 +72,24 @@ pub struct LibraryBook {
   pub title: Title,
   pub rating: u8,
   pub cover: Option<PathBuf>,
+  pub series: Option<String>,
+  pub number: Option<String>,
+  pub volume: Option<i32>,
+  pub summary: Option<String>,
+  pub writer: Option<String>,
+  pub language: Option<String>,
+  pub manga: Option<bool>,
+  pub right_to_left: bool,
+  pub added_at: Option<String>,
+  /// Whether the file couldn't be found, such as when the drive it's on isn't connected.
+  ///
+  /// This isn't stored anywhere, so it's `false` unless the caller checked the file itself.
+  /// The library learns which books are missing from the `BookMissing` event instead.
+  pub missing: bool,
 }
 
 impl LibraryBook {
+  /// The file of the book is never checked here, only whether its cover was extracted.
   pub fn from_model(app: &AppHandle, model: &Book) -> Result<Self> {
     let book = Self {
       id: model.id,
@@ -78,6 +97,16 @@ impl LibraryBook {
       title: Title::new(&model.title),
       rating: u8::try_from(model.rating)?,
       cover: Cover::from_id(app, model.id)?.path_buf(),
+      series: model.series.clone(),
+      number: model.number.clone(),
+      volume: model.volume,
+      summary: model.summary.clone(),
+      writer: model.writer.clone(),
+      language: model.language.clone(),
+      manga: model.manga,
+      right_to_left: model.right_to_left,
+      added_at: model.added_at.clone(),
+      missing: false,
     };
 
     Ok(book)
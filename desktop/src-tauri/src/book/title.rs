@@ -19,8 +19,14 @@ impl TryFrom<&Path> for Title {
   type Error = crate::error::Error;
 
   fn try_from(path: &Path) -> Result<Self> {
-    let title = path
-      .file_stem()
+    // Folders have no extension, so any dot in their name is part of the title.
+    let name = if path.is_dir() {
+      path.file_name()
+    } else {
+      path.file_stem()
+    };
+
+    let title = name
       .ok_or_else(|| err!(InvalidPath, "{}", path.display()))?
       .to_string_lossy()
       .replace('_', " ");
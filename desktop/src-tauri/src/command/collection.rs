@@ -1,6 +1,59 @@
-use crate::database::model::Collection;
+use crate::book::LibraryBook;
+use crate::database::model::{Collection, SmartFilter};
 use crate::prelude::*;
 
+#[tauri::command]
+pub async fn add_to_collection(app: AppHandle, collection_id: i32, book_id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "add_to_collection", collection_id, book_id);
+
+  app
+    .database_handle()
+    .add_to_collection(collection_id, book_id)
+    .await
+    .map(drop)
+}
+
+#[tauri::command]
+pub async fn create_collection(app: AppHandle, name: String) -> Result<Collection> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "create_collection", %name);
+
+  app
+    .database_handle()
+    .create_collection(&name)
+    .await
+}
+
+#[tauri::command]
+pub async fn create_smart_collection(
+  app: AppHandle,
+  name: String,
+  filter: SmartFilter,
+) -> Result<Collection> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "create_smart_collection", %name, ?filter);
+
+  app
+    .database_handle()
+    .create_smart_collection(&name, filter)
+    .await
+}
+
+#[tauri::command]
+pub async fn get_collection_books(app: AppHandle, id: i32) -> Result<Vec<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_collection_books", collection_id = id);
+
+  app
+    .database_handle()
+    .get_collection_books(id)
+    .await?
+    .iter()
+    .map(|model| LibraryBook::from_model(&app, model))
+    .try_collect()
+}
+
 #[tauri::command]
 pub async fn get_collections(app: AppHandle) -> Result<Vec<Collection>> {
   #[cfg(feature = "tracing")]
@@ -8,3 +61,57 @@ pub async fn get_collections(app: AppHandle) -> Result<Vec<Collection>> {
 
   app.database_handle().get_all_collections().await
 }
+
+#[tauri::command]
+pub async fn remove_collection(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "remove_collection", collection_id = id);
+
+  app.database_handle().remove_collection(id).await
+}
+
+#[tauri::command]
+pub async fn remove_from_collection(
+  app: AppHandle,
+  collection_id: i32,
+  book_id: i32,
+) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "remove_from_collection", collection_id, book_id);
+
+  app
+    .database_handle()
+    .remove_from_collection(collection_id, book_id)
+    .await
+    .map(drop)
+}
+
+#[tauri::command]
+pub async fn rename_collection(app: AppHandle, id: i32, name: String) -> Result<Collection> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "rename_collection", collection_id = id, %name);
+
+  app
+    .database_handle()
+    .rename_collection(id, &name)
+    .await
+}
+
+#[tauri::command]
+pub async fn update_smart_collection(
+  app: AppHandle,
+  id: i32,
+  filter: SmartFilter,
+) -> Result<Collection> {
+  #[cfg(feature = "tracing")]
+  debug!(
+    command = "update_smart_collection",
+    collection_id = id,
+    ?filter
+  );
+
+  app
+    .database_handle()
+    .update_smart_collection(id, filter)
+    .await
+}
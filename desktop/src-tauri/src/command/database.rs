@@ -0,0 +1,34 @@
+use crate::database::backup::{self, DatabaseBackup};
+use crate::prelude::*;
+
+#[tauri::command]
+pub async fn backup_database(app: AppHandle) -> Result<DatabaseBackup> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "backup_database");
+
+  app.database_handle().create_backup().await
+}
+
+#[tauri::command]
+pub async fn get_database_backups(app: AppHandle) -> Result<Vec<DatabaseBackup>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_database_backups");
+
+  backup::get_all(&app)
+}
+
+#[tauri::command]
+pub async fn restore_database_backup(app: AppHandle, path: PathBuf) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "restore_database_backup", path = %path.display());
+
+  backup::restore(&app, &path).await
+}
+
+#[tauri::command]
+pub async fn restore_database_backup_with_dialog(app: AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "restore_database_backup_with_dialog");
+
+  backup::restore_with_dialog(&app).await
+}
@@ -0,0 +1,61 @@
+use crate::database::model::Folder;
+use crate::library;
+use crate::prelude::*;
+
+#[tauri::command]
+pub async fn get_library_folders(app: AppHandle) -> Result<Vec<Folder>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_library_folders");
+
+  library::get_folders(&app).await
+}
+
+#[tauri::command]
+pub async fn relink_library_folder(app: AppHandle, id: i32, path: PathBuf) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "relink_library_folder", folder_id = id, path = %path.display());
+
+  library::relink_folder(&app, id, &path).await
+}
+
+#[tauri::command]
+pub async fn relink_library_folder_with_dialog(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(
+    command = "relink_library_folder_with_dialog",
+    folder_id = id
+  );
+
+  library::relink_folder_with_dialog(&app, id).await
+}
+
+#[tauri::command]
+pub async fn remove_library_folder(app: AppHandle, id: i32, remove_books: bool) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(
+    command = "remove_library_folder",
+    folder_id = id,
+    remove_books
+  );
+
+  library::remove_folder(&app, id, remove_books).await
+}
+
+#[tauri::command]
+pub async fn remove_library_folder_with_dialog(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(
+    command = "remove_library_folder_with_dialog",
+    folder_id = id
+  );
+
+  library::remove_folder_with_dialog(&app, id).await
+}
+
+#[tauri::command]
+pub async fn set_folder_exclude(app: AppHandle, id: i32, exclude: Vec<String>) -> Result<Folder> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "set_folder_exclude", folder_id = id, ?exclude);
+
+  library::set_folder_exclude(&app, id, exclude).await
+}
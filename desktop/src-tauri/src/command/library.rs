@@ -1,5 +1,6 @@
 use crate::book::LibraryBook;
-use crate::library;
+use crate::database::model::{LibraryQuery, PathRewrite, ReadingSession};
+use crate::library::{self, bundle, LibraryPage};
 use crate::prelude::*;
 
 #[tauri::command]
@@ -10,6 +11,45 @@ pub async fn add_to_library_with_dialog(app: AppHandle) -> Result<()> {
   library::add_with_dialog(&app).await
 }
 
+/// Check the library files in the background, as it may take a while for large libraries.
+#[tauri::command]
+pub async fn check_library_files(app: AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "check_library_files");
+
+  spawn(async move {
+    library::check_files(&app)
+      .await
+      .into_err_log(&app);
+  });
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn export_library(app: AppHandle, path: PathBuf) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "export_library", path = %path.display());
+
+  bundle::export(&app, &path).await
+}
+
+#[tauri::command]
+pub async fn export_library_with_dialog(app: AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "export_library_with_dialog");
+
+  bundle::export_with_dialog(&app).await
+}
+
+#[tauri::command]
+pub async fn get_duplicate_books(app: AppHandle) -> Result<Vec<Vec<LibraryBook>>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_duplicate_books");
+
+  library::get_duplicates(&app).await
+}
+
 #[tauri::command]
 pub async fn get_library_books(app: AppHandle) -> Result<Vec<LibraryBook>> {
   #[cfg(feature = "tracing")]
@@ -18,6 +58,61 @@ pub async fn get_library_books(app: AppHandle) -> Result<Vec<LibraryBook>> {
   library::get_all(&app).await
 }
 
+#[tauri::command]
+pub async fn get_reading_history(app: AppHandle, id: i32) -> Result<Vec<ReadingSession>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_reading_history", book_id = id);
+
+  app
+    .database_handle()
+    .get_reading_history(id)
+    .await
+}
+
+#[tauri::command]
+pub async fn get_recently_read(app: AppHandle, limit: usize) -> Result<Vec<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_recently_read", limit);
+
+  app
+    .database_handle()
+    .get_recently_read(limit)
+    .await?
+    .iter()
+    .map(|model| LibraryBook::from_model(&app, model))
+    .try_collect()
+}
+
+#[tauri::command]
+pub async fn import_library(
+  app: AppHandle,
+  path: PathBuf,
+  rewrites: Vec<PathRewrite>,
+) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "import_library", path = %path.display(), ?rewrites);
+
+  bundle::import(&app, &path, rewrites)
+    .await
+    .map(drop)
+}
+
+#[tauri::command]
+pub async fn import_library_with_dialog(app: AppHandle, rewrites: Vec<PathRewrite>) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "import_library_with_dialog", ?rewrites);
+
+  bundle::import_with_dialog(&app, rewrites).await
+}
+
+#[tauri::command]
+pub async fn query_library(app: AppHandle, query: LibraryQuery) -> Result<LibraryPage> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "query_library", ?query);
+
+  library::query(&app, query).await
+}
+
 #[tauri::command]
 pub async fn remove_book(app: AppHandle, id: i32) -> Result<()> {
   #[cfg(feature = "tracing")]
@@ -34,6 +129,18 @@ pub async fn remove_book_with_dialog(app: AppHandle, id: i32) -> Result<()> {
   library::remove_with_dialog(&app, id).await
 }
 
+#[tauri::command]
+pub async fn search_books(
+  app: AppHandle,
+  query: String,
+  limit: Option<usize>,
+) -> Result<Vec<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "search_books", %query, ?limit);
+
+  library::search(&app, &query, limit).await
+}
+
 #[tauri::command]
 pub async fn show_library_book_context_menu(window: Window, book_id: i32) -> Result<()> {
   use crate::menu::context::library_book::{Context, LibraryBookContextMenu};
@@ -1,7 +1,13 @@
 pub mod collection;
+pub mod database;
+pub mod folder;
 pub mod library;
 pub mod reader;
+pub mod series;
+pub mod tag;
+pub mod trash;
 
+use crate::book::BookCacheStats;
 use crate::prelude::*;
 use crate::server;
 use crate::window::WindowExt;
@@ -22,6 +28,20 @@ pub async fn focus_main_window(app: AppHandle) -> Result<()> {
   app.main_window().set_foreground_focus()
 }
 
+/// Meant for debugging, to see how many books are kept open and how often they're evicted.
+#[tauri::command]
+pub async fn get_book_cache_stats(app: AppHandle) -> Result<BookCacheStats> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_book_cache_stats");
+
+  let stats = app.book_handle().cache_stats().await?;
+
+  #[cfg(feature = "tracing")]
+  info!(book_cache = ?stats);
+
+  Ok(stats)
+}
+
 #[tauri::command]
 pub async fn server_port() -> u16 {
   server::port()
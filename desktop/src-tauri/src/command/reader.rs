@@ -1,5 +1,6 @@
 use crate::book::{ActiveBook, ReaderBook};
 use crate::prelude::*;
+use crate::reader::Adjacent;
 use crate::{book, reader};
 
 #[tauri::command]
@@ -18,6 +19,22 @@ pub async fn get_current_reader_book(app: AppHandle, window_id: u16) -> Result<R
   ReaderBook::from_reader(&app, window_id).await
 }
 
+#[tauri::command]
+pub async fn undo_delete_page(app: AppHandle, window_id: u16) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "undo_delete_page", window_id);
+
+  reader::undo_delete_page(&app, window_id).await
+}
+
+#[tauri::command]
+pub async fn update_reader_progress(app: AppHandle, window_id: u16, index: usize) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "update_reader_progress", window_id, index);
+
+  reader::update_progress(&app, window_id, index).await
+}
+
 #[tauri::command]
 pub async fn show_reader_page_context_menu(
   window: Window,
@@ -69,3 +86,19 @@ pub async fn open_book_with_dialog(app: AppHandle) -> Result<()> {
 
   book::open_with_dialog(&app).await
 }
+
+#[tauri::command]
+pub async fn open_next_book(app: AppHandle, window_id: u16) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "open_next_book", window_id);
+
+  reader::open_adjacent(&app, window_id, Adjacent::Next).await
+}
+
+#[tauri::command]
+pub async fn open_previous_book(app: AppHandle, window_id: u16) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "open_previous_book", window_id);
+
+  reader::open_adjacent(&app, window_id, Adjacent::Previous).await
+}
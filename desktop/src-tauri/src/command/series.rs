@@ -0,0 +1,51 @@
+use crate::book::{LibraryBook, SeriesParser};
+use crate::library::series::{self, LibrarySeries};
+use crate::prelude::*;
+
+#[tauri::command]
+pub async fn get_all_series(app: AppHandle) -> Result<Vec<LibrarySeries>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_all_series");
+
+  series::get_all(&app).await
+}
+
+#[tauri::command]
+pub async fn get_next_in_series(app: AppHandle, id: i32) -> Result<Option<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_next_in_series", book_id = id);
+
+  series::get_next(&app, id).await
+}
+
+#[tauri::command]
+pub async fn get_previous_in_series(app: AppHandle, id: i32) -> Result<Option<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_previous_in_series", book_id = id);
+
+  series::get_previous(&app, id).await
+}
+
+#[tauri::command]
+pub async fn get_series_books(app: AppHandle, id: i32) -> Result<Vec<LibraryBook>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_series_books", series_id = id);
+
+  series::get_books(&app, id).await
+}
+
+#[tauri::command]
+pub async fn get_series_patterns(app: AppHandle) -> Result<Vec<String>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_series_patterns");
+
+  SeriesParser::patterns(&app)
+}
+
+#[tauri::command]
+pub async fn set_series_patterns(app: AppHandle, patterns: Vec<String>) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "set_series_patterns", ?patterns);
+
+  series::set_patterns(&app, patterns)
+}
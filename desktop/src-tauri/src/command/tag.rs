@@ -0,0 +1,80 @@
+use crate::database::model::Tag;
+use crate::prelude::*;
+
+#[tauri::command]
+pub async fn add_tag_to_book(app: AppHandle, tag_id: i32, book_id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "add_tag_to_book", tag_id, book_id);
+
+  app
+    .database_handle()
+    .add_tag_to_book(tag_id, book_id)
+    .await
+}
+
+#[tauri::command]
+pub async fn create_tag(app: AppHandle, name: String) -> Result<Tag> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "create_tag", %name);
+
+  app.database_handle().create_tag(&name).await
+}
+
+#[tauri::command]
+pub async fn get_book_tags(app: AppHandle, id: i32) -> Result<Vec<Tag>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_book_tags", book_id = id);
+
+  app.database_handle().get_book_tags(id).await
+}
+
+#[tauri::command]
+pub async fn get_tags(app: AppHandle) -> Result<Vec<Tag>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_tags");
+
+  app.database_handle().get_all_tags().await
+}
+
+#[tauri::command]
+pub async fn remove_tag(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "remove_tag", tag_id = id);
+
+  app.database_handle().remove_tag(id).await
+}
+
+#[tauri::command]
+pub async fn remove_tag_from_book(app: AppHandle, tag_id: i32, book_id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "remove_tag_from_book", tag_id, book_id);
+
+  app
+    .database_handle()
+    .remove_tag_from_book(tag_id, book_id)
+    .await
+}
+
+#[tauri::command]
+pub async fn rename_tag(app: AppHandle, id: i32, name: String) -> Result<Tag> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "rename_tag", tag_id = id, %name);
+
+  app.database_handle().rename_tag(id, &name).await
+}
+
+#[tauri::command]
+pub async fn set_book_tags(app: AppHandle, id: i32, names: Vec<String>) -> Result<Vec<Tag>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "set_book_tags", book_id = id, ?names);
+
+  let database = app.database_handle();
+  let tags = database.set_book_tags(id, names).await?;
+  database
+    .get_book_by_id(id)
+    .await?
+    .save_as_metadata(&app)
+    .await?;
+
+  Ok(tags)
+}
@@ -0,0 +1,35 @@
+use crate::database::model::TrashedPage;
+use crate::prelude::*;
+use crate::trash;
+
+#[tauri::command]
+pub async fn empty_trash_with_dialog(app: AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "empty_trash_with_dialog");
+
+  trash::empty_with_dialog(&app).await
+}
+
+#[tauri::command]
+pub async fn get_trashed_pages(app: AppHandle) -> Result<Vec<TrashedPage>> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "get_trashed_pages");
+
+  trash::get_all(&app).await
+}
+
+#[tauri::command]
+pub async fn purge_trashed_page(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "purge_trashed_page", trash_id = id);
+
+  trash::purge(&app, id).await
+}
+
+#[tauri::command]
+pub async fn restore_trashed_page(app: AppHandle, id: i32) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  debug!(command = "restore_trashed_page", trash_id = id);
+
+  trash::restore(&app, id).await
+}
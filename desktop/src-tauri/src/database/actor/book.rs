@@ -1,11 +1,17 @@
+use super::smart::{self, SmartCache};
+use super::{search, series};
 use crate::bail;
 use crate::book::Title;
 use crate::database::actor::Db;
-use crate::database::model::{Book, NewBook};
+use crate::database::model::{
+  Book, BookPage, Cursor, CursorValue, LibraryQuery, NewBook, SortKey, SortOrder,
+};
 use crate::database::schema::books::dsl::*;
+use crate::error::Error;
 use crate::path::PathExt;
 use crate::result::Result;
 use diesel::prelude::*;
+use itertools::Itertools;
 use std::path::{Path, PathBuf};
 #[cfg(feature = "tracing")]
 use {
@@ -37,6 +43,67 @@ pub(super) fn get_by_path(db: Db, book_path: &Path) -> Result<Book> {
     .map_err(Into::into)
 }
 
+/// Books at the path or anywhere below it.
+pub(super) fn get_by_folder(db: Db, folder: &Path) -> Result<Vec<Book>> {
+  let all = get_all(db)?;
+  let in_folder = all
+    .into_iter()
+    .filter(|book| Path::new(&book.path).starts_with(folder))
+    .collect();
+
+  Ok(in_folder)
+}
+
+pub(super) fn get_by_hash(db: Db, book_hash: &str) -> Result<Vec<Book>> {
+  books
+    .filter(hash.eq(book_hash))
+    .select(Book::as_select())
+    .load::<Book>(db)
+    .map_err(Into::into)
+}
+
+/// Books that share their hash with some other book, grouped by it.
+pub(super) fn get_duplicates(db: Db) -> Result<Vec<Vec<Book>>> {
+  use diesel::dsl::count_star;
+
+  let hashes = books
+    .filter(hash.is_not_null())
+    .group_by(hash)
+    .having(count_star().gt(1))
+    .select(hash)
+    .load::<Option<String>>(db)?;
+
+  let duplicates = books
+    .filter(hash.eq_any(hashes.into_iter().flatten()))
+    .order_by((hash, id))
+    .select(Book::as_select())
+    .load::<Book>(db)?
+    .into_iter()
+    .chunk_by(|book| book.hash.clone())
+    .into_iter()
+    .map(|(_, group)| group.collect())
+    .collect();
+
+  Ok(duplicates)
+}
+
+/// Books saved before hashes were a thing.
+pub(super) fn get_without_added_at(db: Db) -> Result<Vec<Book>> {
+  books
+    .filter(added_at.is_null())
+    .select(Book::as_select())
+    .load::<Book>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_without_hash(db: Db) -> Result<Vec<Book>> {
+  books
+    .filter(hash.is_null())
+    .select(Book::as_select())
+    .load::<Book>(db)
+    .map_err(Into::into)
+}
+
 pub(super) fn get_cover(db: Db, book_id: i32) -> Result<String> {
   books
     .find(book_id)
@@ -95,6 +162,148 @@ pub(super) fn is_empty(db: Db) -> Result<bool> {
     .map_err(Into::into)
 }
 
+/// A page of books, sorted and filtered as the query asks.
+///
+/// Pages are delimited by the sort value and id of their last book, rather than an offset,
+/// so books being added or removed in the meantime don't make the next page skip or repeat any.
+pub(super) fn query(db: Db, cache: &SmartCache, library_query: &LibraryQuery) -> Result<BookPage> {
+  use crate::database::schema::{book_collections, book_tags};
+  use diesel::dsl::sql;
+  use diesel::sql_types::{Bool, Integer, Text};
+
+  let mut query = books.select(Book::as_select()).into_boxed();
+  let filter = &library_query.filter;
+
+  if let Some(yes) = filter.read {
+    query = query.filter(read.eq(yes));
+  }
+
+  if let Some(min_rating) = filter.min_rating {
+    query = query.filter(rating.ge(i32::from(min_rating)));
+  }
+
+  if let Some(max_rating) = filter.max_rating {
+    query = query.filter(rating.le(i32::from(max_rating)));
+  }
+
+  if let Some(folder) = &filter.folder {
+    // Unlike `LIKE`, this is case sensitive and has no wildcards to escape.
+    let prefix = folder_prefix(folder);
+    query = query.filter(
+      sql::<Bool>("substr(books.path, 1, length(")
+        .bind::<Text, _>(prefix.clone())
+        .sql(")) = ")
+        .bind::<Text, _>(prefix),
+    );
+  }
+
+  if let Some(collection_id) = filter.collection {
+    // Members of smart collections are already known.
+    if let Some(members) = smart::members(db, cache, collection_id)? {
+      query = query.filter(id.eq_any(members));
+    } else {
+      let members = book_collections::table
+        .filter(book_collections::collection_id.eq(collection_id))
+        .select(book_collections::book_id);
+      query = query.filter(id.eq_any(members));
+    }
+  }
+
+  for tag_id in &filter.tags {
+    let tagged = book_tags::table
+      .filter(book_tags::tag_id.eq(*tag_id))
+      .select(book_tags::book_id);
+    query = query.filter(id.eq_any(tagged));
+  }
+
+  let key = sort_expression(library_query.sort);
+  let (cmp, direction) = match library_query.order {
+    SortOrder::Asc => (">", "ASC"),
+    SortOrder::Desc => ("<", "DESC"),
+  };
+
+  if let Some(cursor) = &library_query.after {
+    let head = format!("({key}, books.id) {cmp} (");
+    query = match &cursor.value {
+      CursorValue::Integer(value) => query.filter(
+        sql::<Bool>(&head)
+          .bind::<Integer, _>(*value)
+          .sql(", ")
+          .bind::<Integer, _>(cursor.id)
+          .sql(")"),
+      ),
+      CursorValue::Text(value) => query.filter(
+        sql::<Bool>(&head)
+          .bind::<Text, _>(value.clone())
+          .sql(", ")
+          .bind::<Integer, _>(cursor.id)
+          .sql(")"),
+      ),
+    };
+  }
+
+  // One more than asked, to tell whether there's a next page.
+  let limit = library_query.limit();
+  let mut page = query
+    .order(sql::<Text>(&format!(
+      "{key} {direction}, books.id {direction}"
+    )))
+    .limit(i64::try_from(limit)? + 1)
+    .load::<Book>(db)?;
+
+  let next = if page.len() > limit {
+    page.truncate(limit);
+    page
+      .last()
+      .map(|book| cursor_for(db, library_query.sort, book))
+      .transpose()?
+  } else {
+    None
+  };
+
+  Ok(BookPage { books: page, next })
+}
+
+/// SQL expression the books are sorted by.
+/// Nullable values are coalesced, as rows can't be compared against a null.
+fn sort_expression(key: SortKey) -> &'static str {
+  match key {
+    SortKey::Title => "books.title COLLATE natural",
+    SortKey::Rating => "books.rating",
+    SortKey::AddedAt => "COALESCE(books.added_at, '')",
+    SortKey::LastRead => {
+      "COALESCE((SELECT MAX(started_at) FROM reading_sessions WHERE book_id = books.id), '')"
+    }
+  }
+}
+
+fn cursor_for(db: Db, key: SortKey, book: &Book) -> Result<Cursor> {
+  use crate::database::schema::reading_sessions;
+  use diesel::dsl::max;
+
+  let value = match key {
+    SortKey::Title => CursorValue::Text(book.title.clone()),
+    SortKey::Rating => CursorValue::Integer(book.rating),
+    SortKey::AddedAt => CursorValue::Text(book.added_at.clone().unwrap_or_default()),
+    SortKey::LastRead => {
+      let last_read = reading_sessions::table
+        .filter(reading_sessions::book_id.eq(book.id))
+        .select(max(reading_sessions::started_at))
+        .first::<Option<String>>(db)?;
+
+      CursorValue::Text(last_read.unwrap_or_default())
+    }
+  };
+
+  Ok(Cursor { value, id: book.id })
+}
+
+/// The folder followed by a separator, so that `/manga` doesn't match `/manga-old`.
+fn folder_prefix(folder: &str) -> String {
+  let folder = folder.trim_end_matches(['/', '\\']);
+  format!("{folder}{}", std::path::MAIN_SEPARATOR)
+}
+
 pub(super) fn random(db: Db) -> Result<Option<Book>> {
   use rand::seq::SliceRandom;
   use rand::thread_rng;
@@ -107,18 +316,20 @@ pub(super) fn random(db: Db) -> Result<Option<Book>> {
 }
 
 pub(super) fn remove(db: Db, book_id: i32) -> Result<()> {
-  diesel::delete(books.find(book_id))
-    .execute(db)
-    .map(drop)
-    .map_err(Into::into)
+  db.transaction::<_, Error, _>(|db| {
+    diesel::delete(books.find(book_id)).execute(db)?;
+    series::remove_empty(db)?;
+    search::unindex(db, book_id)
+  })
 }
 
 #[cfg(feature = "devtools")]
 pub(super) fn remove_all(db: Db) -> Result<()> {
-  diesel::delete(books)
-    .execute(db)
-    .map(drop)
-    .map_err(Into::into)
+  db.transaction::<_, Error, _>(|db| {
+    diesel::delete(books).execute(db)?;
+    series::clear(db)?;
+    search::clear(db)
+  })
 }
 
 #[cfg_attr(feature = "tracing", instrument(skip(db), level = "trace"))]
@@ -126,10 +337,15 @@ pub(super) fn save(db: Db, new_book: &NewBook) -> Result<Book> {
   #[cfg(feature = "tracing")]
   let start = Instant::now();
 
-  let book = diesel::insert_into(books)
-    .values(new_book)
-    .returning(Book::as_returning())
-    .get_result(db)?;
+  let book = db.transaction::<_, Error, _>(|db| {
+    let book = diesel::insert_into(books)
+      .values(new_book)
+      .returning(Book::as_returning())
+      .get_result(db)?;
+
+    search::index(db, &book)?;
+    Ok(book)
+  })?;
 
   #[cfg(feature = "tracing")]
   trace!(book_id = book.id, "book saved in {:?}", start.elapsed());
@@ -137,6 +353,14 @@ pub(super) fn save(db: Db, new_book: &NewBook) -> Result<Book> {
   Ok(book)
 }
 
+pub(super) fn update_added_at(db: Db, book_id: i32, book_added_at: &str) -> Result<()> {
+  diesel::update(books.find(book_id))
+    .set(added_at.eq(book_added_at))
+    .execute(db)?;
+
+  Ok(())
+}
+
 pub(super) fn update_cover(db: Db, book_id: i32, book_cover: &str) -> Result<Book> {
   diesel::update(books.find(book_id))
     .set(cover.eq(book_cover))
@@ -145,6 +369,50 @@ pub(super) fn update_cover(db: Db, book_id: i32, book_cover: &str) -> Result<Boo
     .map_err(Into::into)
 }
 
+/// Point the book to a new path, but only if it's still at `from`.
+/// Returns `None` if it was already moved elsewhere, so that a book is never moved twice.
+pub(super) fn move_to(db: Db, book_id: i32, from: &Path, to: &Path) -> Result<Option<Book>> {
+  let from = from.try_str()?;
+  let to = to.try_str()?;
+  diesel::update(books.find(book_id).filter(path.eq(from)))
+    .set(path.eq(to))
+    .returning(Book::as_returning())
+    .get_result(db)
+    .optional()
+    .map_err(Into::into)
+}
+
+pub(super) fn update_hash(db: Db, book_id: i32, book_hash: &str) -> Result<Book> {
+  diesel::update(books.find(book_id))
+    .set(hash.eq(book_hash))
+    .returning(Book::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn update_path(db: Db, book_id: i32, book_path: &Path) -> Result<Book> {
+  let book_path = book_path.try_str()?;
+  diesel::update(books.find(book_id))
+    .set(path.eq(book_path))
+    .returning(Book::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn update_progress(
+  db: Db,
+  book_id: i32,
+  page_index: usize,
+  page_name: &str,
+) -> Result<Book> {
+  let page_index = i32::try_from(page_index)?;
+  diesel::update(books.find(book_id))
+    .set((last_page_index.eq(page_index), last_page_name.eq(page_name)))
+    .returning(Book::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
 pub(super) fn update_rating(db: Db, book_id: i32, book_rating: u8) -> Result<Book> {
   if book_rating > 5 {
     bail!(InvalidRating);
@@ -0,0 +1,266 @@
+use super::smart::{self, SmartCache};
+use super::{book, collection, folder, search, tag};
+use crate::bail;
+use crate::database::actor::Db;
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::database::model::{
+  Book, BundleBook, BundleCollection, BundleFolder, Collection, Folder, ImportedBundle,
+  LibraryBundle, NewBook, NewBookCollection, NewCollection, PathRewrite,
+};
+use crate::database::schema::{book_collections, book_tags, books, collections, folders, tags};
+use crate::error::Error;
+use crate::result::Result;
+use ahash::{HashMap, HashSet};
+use chrono::Utc;
+use diesel::prelude::*;
+use itertools::Itertools;
+use std::path::Path;
+
+pub(super) fn export(db: Db) -> Result<LibraryBundle> {
+  let mut collections_by_book = book_collections::table
+    .inner_join(collections::table)
+    .filter(collections::smart_filter.is_null())
+    .select((book_collections::book_id, collections::name))
+    .load::<(i32, String)>(db)?
+    .into_iter()
+    .into_group_map();
+
+  let mut tags_by_book = book_tags::table
+    .inner_join(tags::table)
+    .order_by(tags::name)
+    .select((book_tags::book_id, tags::name))
+    .load::<(i32, String)>(db)?
+    .into_iter()
+    .into_group_map();
+
+  let books = book::get_all(db)?
+    .into_iter()
+    .map(|model| {
+      let collections = collections_by_book
+        .remove(&model.id)
+        .unwrap_or_default();
+      let tags = tags_by_book.remove(&model.id).unwrap_or_default();
+      BundleBook::from_model(model, collections, tags)
+    })
+    .collect();
+
+  let collections = collection::get_all(db)?
+    .into_iter()
+    .map(|it| BundleCollection {
+      name: it.name,
+      smart_filter: it.smart_filter,
+    })
+    .collect();
+
+  let folders = folder::get_models(db)?
+    .into_iter()
+    .map(BundleFolder::from)
+    .collect();
+
+  Ok(LibraryBundle {
+    version: LibraryBundle::VERSION,
+    exported_at: Utc::now().format(ADDED_AT_FORMAT).to_string(),
+    folders,
+    collections,
+    books,
+  })
+}
+
+/// Merge the bundle into the library, rewriting its paths along the way.
+///
+/// Books already in the library, found either by path or hash, take the rating, progress
+/// and so on from the bundle, but keep their own path. Collections and tags are only ever added.
+/// Books that aren't in the library are skipped if their file can't be found, as their path
+/// most likely wasn't rewritten.
+/// Everything happens in a single transaction, so a bad bundle leaves the library untouched.
+pub(super) fn import(
+  db: Db,
+  cache: &mut SmartCache,
+  bundle: &LibraryBundle,
+  rewrites: &[PathRewrite],
+) -> Result<ImportedBundle> {
+  let imported = db.transaction::<_, Error, _>(|db| {
+    let mut imported = ImportedBundle::default();
+    import_folders(db, &mut imported, &bundle.folders, rewrites)?;
+    let regular = import_collections(db, &bundle.collections, rewrites)?;
+
+    let mut matched = HashSet::default();
+    for it in &bundle.books {
+      if !(0..=5).contains(&it.rating) {
+        bail!(InvalidRating);
+      }
+
+      let mut it = it.clone();
+      it.path = PathRewrite::apply(rewrites, &it.path);
+
+      let model = match find_book(db, &it, &matched)? {
+        Some(existing) => {
+          let model = diesel::update(books::table.find(existing.id))
+            .set((
+              books::rating.eq(it.rating),
+              books::read.eq(it.read),
+              books::cover.eq(&it.cover),
+              books::last_page_index.eq(it.last_page_index),
+              books::last_page_name.eq(&it.last_page_name),
+            ))
+            .returning(Book::as_returning())
+            .get_result(db)?;
+
+          imported.updated.push(model.clone());
+          model
+        }
+        None if !Path::new(&it.path).exists() => {
+          imported.skipped += 1;
+          continue;
+        }
+        None => {
+          let model = diesel::insert_into(books::table)
+            .values(NewBook::from(&it))
+            .returning(Book::as_returning())
+            .get_result(db)?;
+
+          search::index(db, &model)?;
+          imported.added.push(model.clone());
+          model
+        }
+      };
+
+      matched.insert(model.id);
+
+      for name in &it.collections {
+        if let Some(collection_id) = regular.get(name) {
+          let new = NewBookCollection {
+            book_id: model.id,
+            collection_id: *collection_id,
+          };
+
+          diesel::insert_or_ignore_into(book_collections::table)
+            .values(&new)
+            .execute(db)?;
+        }
+      }
+
+      for name in &it.tags {
+        let tag = tag::create(db, name)?;
+        tag::add_book(db, tag.id, model.id)?;
+      }
+    }
+
+    Ok(imported)
+  })?;
+
+  // Smart collections may have been added, and books may have entered the existing ones.
+  *cache = smart::load_cache(db)?;
+
+  Ok(imported)
+}
+
+/// Folders that aren't already covered by one in the library are added.
+/// Those that are keep their own exclude patterns.
+fn import_folders(
+  db: Db,
+  imported: &mut ImportedBundle,
+  bundle_folders: &[BundleFolder],
+  rewrites: &[PathRewrite],
+) -> Result<()> {
+  let mut current = folder::get_all(db)?;
+  for it in bundle_folders {
+    // Invalid patterns should never reach the database.
+    it.exclude.matcher("")?;
+
+    let folder_path = PathRewrite::apply(rewrites, &it.path);
+    if current
+      .iter()
+      .any(|folder| Path::new(&folder_path).starts_with(folder))
+    {
+      continue;
+    }
+
+    let model = diesel::insert_into(folders::table)
+      .values((
+        folders::path.eq(&folder_path),
+        folders::exclude.eq(&it.exclude),
+      ))
+      .returning(Folder::as_returning())
+      .get_result(db)?;
+
+    current.push(folder_path.into());
+    imported.folders.push(model);
+  }
+
+  Ok(())
+}
+
+/// Create the collections the library doesn't have yet, matching them by name.
+/// Returns the id of every regular collection in the bundle, by name.
+fn import_collections(
+  db: Db,
+  bundle_collections: &[BundleCollection],
+  rewrites: &[PathRewrite],
+) -> Result<HashMap<String, i32>> {
+  let mut regular = HashMap::default();
+  for it in bundle_collections {
+    let existing = collections::table
+      .filter(collections::name.eq(&it.name))
+      .select(Collection::as_select())
+      .first::<Collection>(db)
+      .optional()?;
+
+    let collection = match (existing, &it.smart_filter) {
+      (Some(existing), _) => existing,
+      (None, None) => collection::create(db, &it.name)?,
+      (None, Some(filter)) => {
+        let mut filter = filter.clone();
+        if let Some(folder) = &filter.folder {
+          filter.folder = Some(PathRewrite::apply(rewrites, folder));
+        }
+
+        filter.validate()?;
+        let new = NewCollection {
+          name: it.name.clone(),
+          smart_filter: Some(filter),
+        };
+
+        diesel::insert_into(collections::table)
+          .values(&new)
+          .returning(Collection::as_returning())
+          .get_result(db)?
+      }
+    };
+
+    // Books can't be manually added to smart collections.
+    if !collection.is_smart() {
+      regular.insert(it.name.clone(), collection.id);
+    }
+  }
+
+  Ok(regular)
+}
+
+/// The book in the library at the same path or, failing that, the only one with the same hash.
+/// Books that were already matched by some other in the bundle are never matched by hash.
+fn find_book(db: Db, it: &BundleBook, matched: &HashSet<i32>) -> Result<Option<Book>> {
+  let by_path = books::table
+    .filter(books::path.eq(&it.path))
+    .select(Book::as_select())
+    .first::<Book>(db)
+    .optional()?;
+
+  if by_path.is_some() {
+    return Ok(by_path);
+  }
+
+  let Some(hash) = &it.hash else {
+    return Ok(None);
+  };
+
+  let mut candidates = book::get_by_hash(db, hash)?;
+  candidates.retain(|book| !matched.contains(&book.id));
+
+  // Telling which copy is which isn't possible when there are many.
+  if candidates.len() == 1 {
+    Ok(candidates.pop())
+  } else {
+    Ok(None)
+  }
+}
@@ -1,12 +1,158 @@
+use crate::bail;
+use crate::database::actor::smart::{self, SmartCache};
 use crate::database::actor::Db;
-use crate::database::model::Collection;
+use crate::database::model::{Book, Collection, NewBookCollection, NewCollection, SmartFilter};
 use crate::database::schema::collections::dsl::*;
+use crate::database::schema::{book_collections, books};
 use crate::result::Result;
 use diesel::prelude::*;
 
+pub(super) fn add_book(db: Db, collection_id: i32, book_id: i32) -> Result<Collection> {
+  ensure_not_smart(db, collection_id)?;
+
+  let new = NewBookCollection { book_id, collection_id };
+  diesel::insert_or_ignore_into(book_collections::table)
+    .values(&new)
+    .execute(db)?;
+
+  get_by_id(db, collection_id)
+}
+
+pub(super) fn create(db: Db, collection_name: &str) -> Result<Collection> {
+  let collection_name = validate_name(collection_name)?;
+  let new = NewCollection {
+    name: collection_name.to_owned(),
+    smart_filter: None,
+  };
+
+  diesel::insert_into(collections)
+    .values(&new)
+    .returning(Collection::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn create_smart(
+  db: Db,
+  cache: &mut SmartCache,
+  collection_name: &str,
+  filter: SmartFilter,
+) -> Result<Collection> {
+  let collection_name = validate_name(collection_name)?;
+  filter.validate()?;
+
+  let new = NewCollection {
+    name: collection_name.to_owned(),
+    smart_filter: Some(filter),
+  };
+
+  let collection = diesel::insert_into(collections)
+    .values(&new)
+    .returning(Collection::as_returning())
+    .get_result(db)?;
+
+  smart::reload(db, cache, &collection)?;
+
+  Ok(collection)
+}
+
 pub(super) fn get_all(db: Db) -> Result<Vec<Collection>> {
   collections
     .select(Collection::as_select())
     .load::<Collection>(db)
     .map_err(Into::into)
 }
+
+pub(super) fn get_books(db: Db, collection_id: i32) -> Result<Vec<Book>> {
+  if let Some(filter) = get_by_id(db, collection_id)?.smart_filter {
+    return smart::evaluate(db, &filter, None);
+  }
+
+  books::table
+    .inner_join(book_collections::table)
+    .filter(book_collections::collection_id.eq(collection_id))
+    .select(Book::as_select())
+    .load::<Book>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_by_book(db: Db, book_id: i32) -> Result<Vec<Collection>> {
+  collections
+    .inner_join(book_collections::table)
+    .filter(book_collections::book_id.eq(book_id))
+    .select(Collection::as_select())
+    .load::<Collection>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_by_id(db: Db, collection_id: i32) -> Result<Collection> {
+  collections
+    .find(collection_id)
+    .select(Collection::as_select())
+    .first::<Collection>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn remove(db: Db, cache: &mut SmartCache, collection_id: i32) -> Result<()> {
+  diesel::delete(collections.find(collection_id)).execute(db)?;
+  cache.remove(&collection_id);
+  Ok(())
+}
+
+pub(super) fn remove_book(db: Db, collection_id: i32, book_id: i32) -> Result<Collection> {
+  ensure_not_smart(db, collection_id)?;
+
+  diesel::delete(book_collections::table.find((book_id, collection_id))).execute(db)?;
+  get_by_id(db, collection_id)
+}
+
+pub(super) fn rename(db: Db, collection_id: i32, collection_name: &str) -> Result<Collection> {
+  let collection_name = validate_name(collection_name)?;
+  diesel::update(collections.find(collection_id))
+    .set(name.eq(collection_name))
+    .returning(Collection::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+/// Replace the filter of a smart collection.
+/// Regular collections can't become smart, as the books added to them would be lost.
+pub(super) fn update_filter(
+  db: Db,
+  cache: &mut SmartCache,
+  collection_id: i32,
+  filter: SmartFilter,
+) -> Result<Collection> {
+  if !get_by_id(db, collection_id)?.is_smart() {
+    bail!(NotSmartCollection);
+  }
+
+  filter.validate()?;
+
+  let collection = diesel::update(collections.find(collection_id))
+    .set(smart_filter.eq(Some(filter)))
+    .returning(Collection::as_returning())
+    .get_result(db)?;
+
+  smart::reload(db, cache, &collection)?;
+
+  Ok(collection)
+}
+
+/// Books can't be manually added to or removed from smart collections.
+fn ensure_not_smart(db: Db, collection_id: i32) -> Result<()> {
+  if get_by_id(db, collection_id)?.is_smart() {
+    bail!(SmartCollection);
+  }
+
+  Ok(())
+}
+
+fn validate_name(collection_name: &str) -> Result<&str> {
+  let collection_name = collection_name.trim();
+  if collection_name.is_empty() {
+    bail!(InvalidCollectionName);
+  }
+
+  Ok(collection_name)
+}
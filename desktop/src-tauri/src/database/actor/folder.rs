@@ -1,10 +1,13 @@
+use super::book;
 use crate::database::actor::Db;
-use crate::database::model::NewFolder;
+use crate::database::model::{Exclude, Folder, NewFolder, RelinkedFolder};
 use crate::database::schema::folders::dsl::*;
+use crate::error::Error;
+use crate::path::PathExt;
 use crate::result::Result;
 use diesel::prelude::*;
 use itertools::Itertools;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub(super) fn get_all(db: Db) -> Result<Vec<PathBuf>> {
   folders
@@ -14,6 +17,13 @@ pub(super) fn get_all(db: Db) -> Result<Vec<PathBuf>> {
     .map_err(Into::into)
 }
 
+pub(super) fn get_models(db: Db) -> Result<Vec<Folder>> {
+  folders
+    .select(Folder::as_select())
+    .load::<Folder>(db)
+    .map_err(Into::into)
+}
+
 pub(super) fn is_empty(db: Db) -> Result<bool> {
   use diesel::dsl::count_star;
 
@@ -25,6 +35,61 @@ pub(super) fn is_empty(db: Db) -> Result<bool> {
     .map_err(Into::into)
 }
 
+/// Point the folder, along with every folder and book below it, to a new path.
+/// Everything happens in a single transaction, so the library is never left half relinked.
+pub(super) fn relink(db: Db, folder_id: i32, folder_path: &Path) -> Result<RelinkedFolder> {
+  db.transaction::<_, Error, _>(|db| {
+    let folder = folders
+      .find(folder_id)
+      .select(Folder::as_select())
+      .first::<Folder>(db)?;
+
+    let from = PathBuf::from(&folder.path);
+    let nested = get_models(db)?
+      .into_iter()
+      .filter(|it| Path::new(&it.path).starts_with(&from));
+
+    for it in nested {
+      let new_path = it.path.try_rebase(&from, folder_path)?;
+      let new_path = new_path.try_str()?;
+
+      // The new path may have already been added to the library on its own.
+      diesel::delete(folders.filter(path.eq(new_path).and(id.ne(it.id)))).execute(db)?;
+      diesel::update(folders.find(it.id))
+        .set(path.eq(new_path))
+        .execute(db)?;
+    }
+
+    let mut relinked = Vec::new();
+    let mut replaced = Vec::new();
+    for model in book::get_by_folder(db, &from)? {
+      let book_path = model.path.try_rebase(&from, folder_path)?;
+      if let Ok(existing) = book::get_by_path(db, &book_path)
+        && existing.id != model.id
+      {
+        book::remove(db, existing.id)?;
+        replaced.push(existing.id);
+      }
+
+      relinked.push(book::update_path(db, model.id, &book_path)?);
+    }
+
+    let folder = folders
+      .find(folder_id)
+      .select(Folder::as_select())
+      .first::<Folder>(db)?;
+
+    Ok(RelinkedFolder { folder, books: relinked, replaced })
+  })
+}
+
+pub(super) fn remove(db: Db, folder_id: i32) -> Result<Folder> {
+  diesel::delete(folders.find(folder_id))
+    .returning(Folder::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
 #[cfg(feature = "devtools")]
 pub(super) fn remove_all(db: Db) -> Result<()> {
   diesel::delete(folders)
@@ -44,3 +109,14 @@ pub(super) fn save_many(db: Db, new_folders: &[NewFolder]) -> Result<()> {
     .map(drop)
     .map_err(Into::into)
 }
+
+pub(super) fn update_exclude(db: Db, folder_id: i32, folder_exclude: &Exclude) -> Result<Folder> {
+  // Invalid patterns should never reach the database.
+  folder_exclude.matcher("")?;
+
+  diesel::update(folders.find(folder_id))
+    .set(exclude.eq(folder_exclude))
+    .returning(Folder::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
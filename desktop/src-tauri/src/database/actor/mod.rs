@@ -1,9 +1,18 @@
 mod book;
+mod bundle;
 mod collection;
 mod folder;
+mod search;
+mod series;
+mod session;
+mod smart;
+mod tag;
+mod trash;
 
+use crate::database::backup;
 use crate::database::message::Message;
 use diesel::sqlite::SqliteConnection;
+use smart::SmartCache;
 use std::sync::mpsc;
 
 type Db<'a> = &'a mut SqliteConnection;
@@ -17,11 +26,19 @@ macro_rules! send {
 pub(super) struct Actor {
   db: SqliteConnection,
   receiver: mpsc::Receiver<Message>,
+  smart: SmartCache,
 }
 
 impl Actor {
-  pub(super) fn new(db: SqliteConnection, receiver: mpsc::Receiver<Message>) -> Self {
-    Self { db, receiver }
+  pub(super) fn new(mut db: SqliteConnection, receiver: mpsc::Receiver<Message>) -> Self {
+    let smart = smart::load_cache(&mut db).unwrap_or_else(|_err| {
+      #[cfg(feature = "tracing")]
+      tracing::error!("failed to evaluate smart collections: {_err}");
+
+      SmartCache::default()
+    });
+
+    Self { db, receiver, smart }
   }
 
   pub(super) fn run(&mut self) {
@@ -35,6 +52,49 @@ impl Actor {
     tracing::trace!(%message);
 
     match message {
+      Message::AddToCollection { collection_id, book_id, tx } => {
+        send!(
+          tx,
+          collection::add_book(&mut self.db, collection_id, book_id)
+        );
+      }
+      Message::AddTagToBook { tag_id, book_id, tx } => {
+        send!(tx, tag::add_book(&mut self.db, tag_id, book_id));
+      }
+      Message::AddToTrash { page, tx } => {
+        send!(tx, trash::add(&mut self.db, &page));
+      }
+      Message::AssignSeries { book_id, name, volume, chapter, tx } => {
+        send!(
+          tx,
+          series::assign(&mut self.db, book_id, &name, volume, chapter)
+        );
+      }
+      Message::ClearSeries { tx } => {
+        send!(tx, series::clear(&mut self.db));
+      }
+      Message::CreateBackup { database, dir, tx } => {
+        send!(tx, backup::create(&mut self.db, &database, &dir));
+      }
+      Message::CreateCollection { name, tx } => {
+        send!(tx, collection::create(&mut self.db, &name));
+      }
+      Message::CreateSmartCollection { name, filter, tx } => {
+        let result = collection::create_smart(&mut self.db, &mut self.smart, &name, filter);
+        send!(tx, result);
+      }
+      Message::CreateTag { name, tx } => {
+        send!(tx, tag::create(&mut self.db, &name));
+      }
+      Message::EndReadingSession { session_id, pages_viewed, tx } => {
+        send!(tx, session::end(&mut self.db, session_id, pages_viewed));
+      }
+      Message::EmptyTrash { tx } => {
+        send!(tx, trash::remove_all(&mut self.db));
+      }
+      Message::ExportLibrary { tx } => {
+        send!(tx, bundle::export(&mut self.db));
+      }
       Message::GetAllBooks { tx } => {
         send!(tx, book::get_all(&mut self.db));
       }
@@ -44,21 +104,84 @@ impl Actor {
       Message::GetAllFolders { tx } => {
         send!(tx, folder::get_all(&mut self.db));
       }
+      Message::GetAllSeries { tx } => {
+        send!(tx, series::get_all(&mut self.db));
+      }
+      Message::GetAllTags { tx } => {
+        send!(tx, tag::get_all(&mut self.db));
+      }
+      Message::GetAllTrashedPages { tx } => {
+        send!(tx, trash::get_all(&mut self.db));
+      }
+      Message::GetAdjacentInSeries { book_id, step, tx } => {
+        send!(tx, series::get_adjacent(&mut self.db, book_id, step));
+      }
       Message::GetBookById { book_id, tx } => {
         send!(tx, book::get_by_id(&mut self.db, book_id));
       }
       Message::GetBookByPath { book_path, tx } => {
         send!(tx, book::get_by_path(&mut self.db, &book_path));
       }
+      Message::GetBooksInFolder { folder, tx } => {
+        send!(tx, book::get_by_folder(&mut self.db, &folder));
+      }
+      Message::GetBooksByHash { book_hash, tx } => {
+        send!(tx, book::get_by_hash(&mut self.db, &book_hash));
+      }
+      Message::GetBooksWithoutAddedAt { tx } => {
+        send!(tx, book::get_without_added_at(&mut self.db));
+      }
+      Message::GetBooksWithoutHash { tx } => {
+        send!(tx, book::get_without_hash(&mut self.db));
+      }
+      Message::GetBooksWithoutSeries { tx } => {
+        send!(tx, series::get_unassigned(&mut self.db));
+      }
       Message::GetBookCover { book_id, tx } => {
         send!(tx, book::get_cover(&mut self.db, book_id));
       }
       Message::GetBookPath { book_id, tx } => {
         send!(tx, book::get_path(&mut self.db, book_id));
       }
+      Message::GetBookCollections { book_id, tx } => {
+        send!(tx, collection::get_by_book(&mut self.db, book_id));
+      }
+      Message::GetBookSeries { book_id, tx } => {
+        send!(tx, series::get_by_book(&mut self.db, book_id));
+      }
+      Message::GetBookTags { book_id, tx } => {
+        send!(tx, tag::get_by_book(&mut self.db, book_id));
+      }
       Message::GetBookTitle { book_id, tx } => {
         send!(tx, book::get_title(&mut self.db, book_id));
       }
+      Message::GetCollectionBooks { collection_id, tx } => {
+        send!(tx, collection::get_books(&mut self.db, collection_id));
+      }
+      Message::GetDuplicateBooks { tx } => {
+        send!(tx, book::get_duplicates(&mut self.db));
+      }
+      Message::GetSeriesBooks { series_id, tx } => {
+        send!(tx, series::get_books(&mut self.db, series_id));
+      }
+      Message::GetReadingHistory { book_id, tx } => {
+        send!(tx, session::get_by_book(&mut self.db, book_id));
+      }
+      Message::GetRecentlyRead { limit, tx } => {
+        send!(tx, session::get_recent_books(&mut self.db, limit));
+      }
+      Message::GetLibraryFolders { tx } => {
+        send!(tx, folder::get_models(&mut self.db));
+      }
+      Message::GetLatestTrashedPage { book_path, tx } => {
+        send!(tx, trash::get_latest(&mut self.db, &book_path));
+      }
+      Message::GetTagBooks { tag_id, tx } => {
+        send!(tx, tag::get_books(&mut self.db, tag_id));
+      }
+      Message::GetTrashedPage { trash_id, tx } => {
+        send!(tx, trash::get_by_id(&mut self.db, trash_id));
+      }
       Message::HasAnyBook { tx } => {
         send!(tx, book::is_empty(&mut self.db));
       }
@@ -68,27 +191,117 @@ impl Actor {
       Message::HasBookPath { book_path, tx } => {
         send!(tx, book::has_path(&mut self.db, &book_path));
       }
+      Message::ImportLibrary { bundle, rewrites, tx } => {
+        let result = bundle::import(&mut self.db, &mut self.smart, &bundle, &rewrites);
+        send!(tx, result);
+      }
+      Message::MoveBook { book_id, from, to, tx } => {
+        send!(tx, book::move_to(&mut self.db, book_id, &from, &to));
+      }
+      Message::QueryBooks { query, tx } => {
+        send!(tx, book::query(&mut self.db, &self.smart, &query));
+      }
       Message::RandomBook { tx } => {
         send!(tx, book::random(&mut self.db));
       }
+      Message::ExpireSmartCollections { tx } => {
+        send!(tx, smart::expire(&mut self.db, &mut self.smart));
+      }
+      Message::RefreshSmartCollections { book_id, tx } => {
+        send!(tx, smart::refresh(&mut self.db, &mut self.smart, book_id));
+      }
+      Message::RelinkFolder { folder_id, folder_path, tx } => {
+        send!(tx, folder::relink(&mut self.db, folder_id, &folder_path));
+      }
       Message::RemoveBook { book_id, tx } => {
         send!(tx, book::remove(&mut self.db, book_id));
       }
+      Message::RemoveCollection { collection_id, tx } => {
+        send!(
+          tx,
+          collection::remove(&mut self.db, &mut self.smart, collection_id)
+        );
+      }
+      Message::RemoveEmptySeries { tx } => {
+        send!(tx, series::remove_empty(&mut self.db));
+      }
+      Message::RemoveFolder { folder_id, tx } => {
+        send!(tx, folder::remove(&mut self.db, folder_id));
+      }
+      Message::RemoveFromCollection { collection_id, book_id, tx } => {
+        send!(
+          tx,
+          collection::remove_book(&mut self.db, collection_id, book_id)
+        );
+      }
+      Message::RemoveFromTrash { trash_id, tx } => {
+        send!(tx, trash::remove(&mut self.db, trash_id));
+      }
+      Message::RemoveTag { tag_id, tx } => {
+        send!(tx, tag::remove(&mut self.db, tag_id));
+      }
+      Message::RemoveTagFromBook { tag_id, book_id, tx } => {
+        send!(tx, tag::remove_book(&mut self.db, tag_id, book_id));
+      }
+      Message::RenameCollection { collection_id, name, tx } => {
+        send!(tx, collection::rename(&mut self.db, collection_id, &name));
+      }
+      Message::RenameTag { tag_id, name, tx } => {
+        send!(tx, tag::rename(&mut self.db, tag_id, &name));
+      }
       Message::SaveBook { book, tx } => {
         send!(tx, book::save(&mut self.db, &book));
       }
       Message::SaveFolders { folders, tx } => {
         send!(tx, folder::save_many(&mut self.db, &folders));
       }
+      Message::SearchBooks { query, limit, tx } => {
+        send!(tx, search::search(&mut self.db, &query, limit));
+      }
+      Message::SetBookTags { book_id, names, tx } => {
+        send!(tx, tag::set_book_tags(&mut self.db, book_id, &names));
+      }
+      Message::StartReadingSession { session, tx } => {
+        send!(tx, session::start(&mut self.db, &session));
+      }
+      Message::UnassignSeries { book_id, tx } => {
+        send!(tx, series::unassign(&mut self.db, book_id));
+      }
+      Message::UpdateBookAddedAt { book_id, added_at, tx } => {
+        send!(tx, book::update_added_at(&mut self.db, book_id, &added_at));
+      }
       Message::UpdateBookCover { book_id, cover, tx } => {
         send!(tx, book::update_cover(&mut self.db, book_id, &cover));
       }
+      Message::UpdateBookHash { book_id, book_hash, tx } => {
+        send!(tx, book::update_hash(&mut self.db, book_id, &book_hash));
+      }
+      Message::UpdateBookPath { book_id, book_path, tx } => {
+        send!(tx, book::update_path(&mut self.db, book_id, &book_path));
+      }
+      Message::UpdateBookProgress { book_id, page_index, page_name, tx } => {
+        send!(
+          tx,
+          book::update_progress(&mut self.db, book_id, page_index, &page_name)
+        );
+      }
       Message::UpdateBookRating { book_id, rating, tx } => {
         send!(tx, book::update_rating(&mut self.db, book_id, rating));
       }
       Message::UpdateBookRead { book_id, read, tx } => {
         send!(tx, book::update_read(&mut self.db, book_id, read));
       }
+      Message::UpdateFolderExclude { folder_id, exclude, tx } => {
+        send!(
+          tx,
+          folder::update_exclude(&mut self.db, folder_id, &exclude)
+        );
+      }
+      Message::UpdateSmartCollection { collection_id, filter, tx } => {
+        let result =
+          collection::update_filter(&mut self.db, &mut self.smart, collection_id, filter);
+        send!(tx, result);
+      }
 
       #[cfg(feature = "devtools")]
       Message::RemoveAllBooks { tx } => {
@@ -0,0 +1,98 @@
+//! Full-text search over the library, backed by the `books_fts` virtual table.
+//! Diesel knows nothing about virtual tables, so it's queried with plain SQL.
+
+use crate::database::actor::Db;
+use crate::database::model::Book;
+use crate::database::schema::books;
+use crate::result::Result;
+use ahash::{HashMap, HashMapExt};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text};
+use itertools::Itertools;
+
+/// How much a match in each column (title, series and writer) weighs in the ranking.
+const RANK: &str = "bm25(books_fts, 10.0, 5.0, 2.0)";
+
+#[derive(QueryableByName)]
+struct Match {
+  #[diesel(sql_type = Integer)]
+  rowid: i32,
+}
+
+/// Add the book to the index, replacing whatever was there for it.
+pub(super) fn index(db: Db, book: &Book) -> Result<()> {
+  unindex(db, book.id)?;
+  diesel::sql_query("INSERT INTO books_fts (rowid, title, series, writer) VALUES (?, ?, ?, ?)")
+    .bind::<Integer, _>(book.id)
+    .bind::<Text, _>(&book.title)
+    .bind::<Nullable<Text>, _>(&book.series)
+    .bind::<Nullable<Text>, _>(&book.writer)
+    .execute(db)
+    .map(drop)
+    .map_err(Into::into)
+}
+
+pub(super) fn unindex(db: Db, book_id: i32) -> Result<()> {
+  diesel::sql_query("DELETE FROM books_fts WHERE rowid = ?")
+    .bind::<Integer, _>(book_id)
+    .execute(db)
+    .map(drop)
+    .map_err(Into::into)
+}
+
+#[cfg(feature = "devtools")]
+pub(super) fn clear(db: Db) -> Result<()> {
+  diesel::sql_query("DELETE FROM books_fts")
+    .execute(db)
+    .map(drop)
+    .map_err(Into::into)
+}
+
+/// Books matching every word in the query, from the most relevant.
+///
+/// Words are matched as prefixes, and diacritics are ignored on both sides,
+/// so `poke` finds "Pokémon".
+pub(super) fn search(db: Db, query: &str, limit: Option<usize>) -> Result<Vec<Book>> {
+  let Some(expression) = match_expression(query) else {
+    return Ok(Vec::new());
+  };
+
+  // A negative limit means there's none.
+  let limit = limit.map_or(Ok(-1), i64::try_from)?;
+  let sql = format!("SELECT rowid FROM books_fts WHERE books_fts MATCH ? ORDER BY {RANK} LIMIT ?");
+  let ids = diesel::sql_query(sql)
+    .bind::<Text, _>(expression)
+    .bind::<BigInt, _>(limit)
+    .load::<Match>(db)?
+    .into_iter()
+    .map(|it| it.rowid)
+    .collect_vec();
+
+  let mut rank = HashMap::with_capacity(ids.len());
+  for (position, book_id) in ids.iter().enumerate() {
+    rank.insert(*book_id, position);
+  }
+
+  let mut found = books::table
+    .filter(books::id.eq_any(&ids))
+    .select(Book::as_select())
+    .load::<Book>(db)?;
+
+  found.sort_by_key(|book| rank.get(&book.id).copied());
+
+  Ok(found)
+}
+
+/// Turn each word into a quoted prefix query, so nothing the user types is taken as FTS5 syntax.
+fn match_expression(query: &str) -> Option<String> {
+  let terms = query
+    .split_whitespace()
+    .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+    .collect_vec();
+
+  if terms.is_empty() {
+    None
+  } else {
+    Some(terms.join(" "))
+  }
+}
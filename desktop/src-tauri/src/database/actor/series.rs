@@ -0,0 +1,169 @@
+use crate::database::actor::Db;
+use crate::database::model::{Book, NewSeries, Series, SeriesBook};
+use crate::database::schema::{books, series, series_books};
+use crate::error::Error;
+use crate::result::Result;
+use diesel::prelude::*;
+use itertools::Itertools;
+use std::cmp::Ordering;
+
+/// Put the book in the series with the given name, creating it if needed.
+/// A book belongs to a single series, so it's taken out of any other.
+///
+/// The series it leaves may end up empty; see [`remove_empty`].
+pub(super) fn assign(
+  db: Db,
+  book_id: i32,
+  series_name: &str,
+  volume: Option<i32>,
+  chapter: Option<f64>,
+) -> Result<SeriesBook> {
+  db.transaction::<_, Error, _>(|db| {
+    diesel::insert_into(series::table)
+      .values(NewSeries { name: series_name.to_owned() })
+      .on_conflict_do_nothing()
+      .execute(db)?;
+
+    // Names are compared without regard to case.
+    let series_id = series::table
+      .filter(series::name.eq(series_name))
+      .select(series::id)
+      .first::<i32>(db)?;
+
+    let entry = SeriesBook { book_id, series_id, volume, chapter };
+    diesel::replace_into(series_books::table)
+      .values(&entry)
+      .execute(db)?;
+
+    Ok(entry)
+  })
+}
+
+pub(super) fn unassign(db: Db, book_id: i32) -> Result<()> {
+  diesel::delete(series_books::table.find(book_id)).execute(db)?;
+  Ok(())
+}
+
+/// Every series, along with its books in reading order.
+pub(super) fn get_all(db: Db) -> Result<Vec<(Series, Vec<Book>)>> {
+  let rows = series_books::table
+    .inner_join(books::table)
+    .inner_join(series::table)
+    .select((
+      Series::as_select(),
+      SeriesBook::as_select(),
+      Book::as_select(),
+    ))
+    .load::<(Series, SeriesBook, Book)>(db)?;
+
+  let all = rows
+    .into_iter()
+    .sorted_by(|(a, a_entry, a_book), (b, b_entry, b_book)| {
+      natord::compare_ignore_case(&a.name, &b.name)
+        .then(a.id.cmp(&b.id))
+        .then_with(|| compare((a_entry, a_book), (b_entry, b_book)))
+    })
+    .chunk_by(|(series, _, _)| series.id)
+    .into_iter()
+    .filter_map(|(_, group)| {
+      let mut group = group.peekable();
+      let series = group.peek()?.0.clone();
+      let books = group.map(|(_, _, book)| book).collect();
+      Some((series, books))
+    })
+    .collect();
+
+  Ok(all)
+}
+
+pub(super) fn get_by_book(db: Db, book_id: i32) -> Result<Option<SeriesBook>> {
+  series_books::table
+    .find(book_id)
+    .select(SeriesBook::as_select())
+    .first::<SeriesBook>(db)
+    .optional()
+    .map_err(Into::into)
+}
+
+/// Books of the series, in reading order.
+pub(super) fn get_books(db: Db, series_id: i32) -> Result<Vec<Book>> {
+  let rows = series_books::table
+    .inner_join(books::table)
+    .filter(series_books::series_id.eq(series_id))
+    .select((SeriesBook::as_select(), Book::as_select()))
+    .load::<(SeriesBook, Book)>(db)?;
+
+  let books = rows
+    .into_iter()
+    .sorted_by(|(a, a_book), (b, b_book)| compare((a, a_book), (b, b_book)))
+    .map(|(_, book)| book)
+    .collect();
+
+  Ok(books)
+}
+
+/// The book that comes `step` places after (or before, if negative) the given one in its series.
+pub(super) fn get_adjacent(db: Db, book_id: i32, step: isize) -> Result<Option<Book>> {
+  let series_id = series_books::table
+    .find(book_id)
+    .select(series_books::series_id)
+    .first::<i32>(db)
+    .optional()?;
+
+  let Some(series_id) = series_id else {
+    return Ok(None);
+  };
+
+  let mut books = get_books(db, series_id)?;
+  let adjacent = books
+    .iter()
+    .position(|book| book.id == book_id)
+    .and_then(|index| index.checked_add_signed(step))
+    .filter(|index| *index < books.len())
+    .map(|index| books.swap_remove(index));
+
+  Ok(adjacent)
+}
+
+/// Books that don't belong to any series yet.
+pub(super) fn get_unassigned(db: Db) -> Result<Vec<Book>> {
+  books::table
+    .left_join(series_books::table)
+    .filter(series_books::book_id.is_null())
+    .select(Book::as_select())
+    .load::<Book>(db)
+    .map_err(Into::into)
+}
+
+/// Forget every series, so that they can be parsed again.
+pub(super) fn clear(db: Db) -> Result<()> {
+  diesel::delete(series::table).execute(db)?;
+  Ok(())
+}
+
+/// Remove the series left without any book.
+pub(super) fn remove_empty(db: Db) -> Result<()> {
+  let used = series_books::table.select(series_books::series_id);
+  diesel::delete(series::table.filter(series::id.ne_all(used))).execute(db)?;
+  Ok(())
+}
+
+/// Volumes come first, then chapters, with books lacking them after the rest.
+/// The title settles what's left, so that `Chapter 2` comes before `Chapter 10`.
+fn compare(a: (&SeriesBook, &Book), b: (&SeriesBook, &Book)) -> Ordering {
+  fn nulls_last<T>(a: Option<T>, b: Option<T>, cmp: impl FnOnce(T, T) -> Ordering) -> Ordering {
+    match (a, b) {
+      (Some(a), Some(b)) => cmp(a, b),
+      (Some(_), None) => Ordering::Less,
+      (None, Some(_)) => Ordering::Greater,
+      (None, None) => Ordering::Equal,
+    }
+  }
+
+  let (a_entry, a_book) = a;
+  let (b_entry, b_book) = b;
+  nulls_last(a_entry.volume, b_entry.volume, |a, b| a.cmp(&b))
+    .then_with(|| nulls_last(a_entry.chapter, b_entry.chapter, |a, b| a.total_cmp(&b)))
+    .then_with(|| natord::compare_ignore_case(&a_book.title, &b_book.title))
+    .then(a_book.id.cmp(&b_book.id))
+}
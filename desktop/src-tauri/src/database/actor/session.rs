@@ -0,0 +1,57 @@
+use crate::database::actor::Db;
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::database::model::{Book, NewReadingSession, ReadingSession};
+use crate::database::schema::books;
+use crate::database::schema::reading_sessions::dsl::*;
+use crate::result::Result;
+use chrono::Utc;
+use diesel::prelude::*;
+
+pub(super) fn start(db: Db, new_session: &NewReadingSession) -> Result<ReadingSession> {
+  diesel::insert_into(reading_sessions)
+    .values(new_session)
+    .returning(ReadingSession::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn end(db: Db, session_id: i32, viewed: usize) -> Result<()> {
+  let now = Utc::now().format(ADDED_AT_FORMAT).to_string();
+  let viewed = i32::try_from(viewed)?;
+  diesel::update(reading_sessions.find(session_id))
+    .set((ended_at.eq(now), pages_viewed.eq(viewed)))
+    .execute(db)
+    .map(drop)
+    .map_err(Into::into)
+}
+
+/// Sessions of the book, from the most recent.
+pub(super) fn get_by_book(db: Db, session_book_id: i32) -> Result<Vec<ReadingSession>> {
+  reading_sessions
+    .filter(book_id.eq(session_book_id))
+    .order((started_at.desc(), id.desc()))
+    .select(ReadingSession::as_select())
+    .load(db)
+    .map_err(Into::into)
+}
+
+/// Books with the most recent sessions, each appearing only once.
+pub(super) fn get_recent_books(db: Db, limit: usize) -> Result<Vec<Book>> {
+  use diesel::dsl::max;
+
+  let ids = reading_sessions
+    .group_by(book_id)
+    .select(book_id)
+    .order(max(started_at).desc())
+    .limit(i64::try_from(limit)?)
+    .load::<i32>(db)?;
+
+  let mut recent = books::table
+    .filter(books::id.eq_any(&ids))
+    .select(Book::as_select())
+    .load::<Book>(db)?;
+
+  recent.sort_by_key(|book| ids.iter().position(|it| *it == book.id));
+
+  Ok(recent)
+}
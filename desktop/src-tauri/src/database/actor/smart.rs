@@ -0,0 +1,150 @@
+use crate::database::actor::Db;
+use crate::database::model::{Book, Collection, SmartFilter};
+use crate::database::schema::{books, collections};
+use crate::result::Result;
+use ahash::{HashMap, HashSet};
+use diesel::prelude::*;
+use std::path::Path;
+
+/// Books currently matching each smart collection, used to tell which ones changed after an update.
+pub(super) type SmartCache = HashMap<i32, HashSet<i32>>;
+
+pub(super) fn load_cache(db: Db) -> Result<SmartCache> {
+  let mut cache = SmartCache::default();
+  for collection in get_all(db)? {
+    if let Some(filter) = &collection.smart_filter {
+      let members = evaluate(db, filter, None)?;
+      cache.insert(collection.id, to_ids(&members));
+    }
+  }
+
+  Ok(cache)
+}
+
+/// Books matching the filter, optionally restricted to a single one.
+pub(super) fn evaluate(db: Db, filter: &SmartFilter, book_id: Option<i32>) -> Result<Vec<Book>> {
+  let mut query = books::table
+    .select(Book::as_select())
+    .into_boxed();
+
+  if let Some(book_id) = book_id {
+    query = query.filter(books::id.eq(book_id));
+  }
+
+  if let Some(min_rating) = filter.min_rating {
+    query = query.filter(books::rating.ge(i32::from(min_rating)));
+  }
+
+  if let Some(read) = filter.read {
+    query = query.filter(books::read.eq(read));
+  }
+
+  // Books that don't know when they were added are never matched.
+  if let Some(since) = filter.added_since()? {
+    query = query.filter(books::added_at.ge(since));
+  }
+
+  let mut matches = query.load::<Book>(db)?;
+
+  if let Some(folder) = &filter.folder {
+    matches.retain(|book| Path::new(&book.path).starts_with(folder));
+  }
+
+  if let Some(matcher) = filter.title_matcher()? {
+    matches.retain(|book| matcher.is_match(&book.title));
+  }
+
+  Ok(matches)
+}
+
+/// Members of the collection, or `None` if it isn't a smart one.
+/// Filters relative to the current time are evaluated again, as what's cached may be out of date.
+pub(super) fn members(db: Db, cache: &SmartCache, collection_id: i32) -> Result<Option<Vec<i32>>> {
+  let Some(members) = cache.get(&collection_id) else {
+    return Ok(None);
+  };
+
+  let filter = collections::table
+    .find(collection_id)
+    .select(collections::smart_filter)
+    .first::<Option<SmartFilter>>(db)?;
+
+  if let Some(filter) = filter
+    && filter.is_relative()
+  {
+    let members = evaluate(db, &filter, None)?;
+    return Ok(Some(members.iter().map(|it| it.id).collect()));
+  }
+
+  Ok(Some(members.iter().copied().collect()))
+}
+
+/// Evaluate again the filters relative to the current time, returning the collections that changed.
+pub(super) fn expire(db: Db, cache: &mut SmartCache) -> Result<Vec<Collection>> {
+  let mut changed = Vec::new();
+  for collection in get_all(db)? {
+    let Some(filter) = &collection.smart_filter else {
+      continue;
+    };
+
+    if !filter.is_relative() {
+      continue;
+    }
+
+    let members = to_ids(&evaluate(db, filter, None)?);
+    if cache.get(&collection.id) != Some(&members) {
+      cache.insert(collection.id, members);
+      changed.push(collection);
+    }
+  }
+
+  Ok(changed)
+}
+
+/// Evaluate the filter from scratch, replacing whatever was cached for the collection.
+pub(super) fn reload(db: Db, cache: &mut SmartCache, collection: &Collection) -> Result<()> {
+  if let Some(filter) = &collection.smart_filter {
+    let members = evaluate(db, filter, None)?;
+    cache.insert(collection.id, to_ids(&members));
+  } else {
+    cache.remove(&collection.id);
+  }
+
+  Ok(())
+}
+
+/// Check the book against every smart collection, returning those it entered or left.
+pub(super) fn refresh(db: Db, cache: &mut SmartCache, book_id: i32) -> Result<Vec<Collection>> {
+  let mut changed = Vec::new();
+  for collection in get_all(db)? {
+    let Some(filter) = &collection.smart_filter else {
+      continue;
+    };
+
+    let matches = !evaluate(db, filter, Some(book_id))?.is_empty();
+    let members = cache.entry(collection.id).or_default();
+    let is_changed = if matches {
+      members.insert(book_id)
+    } else {
+      members.remove(&book_id)
+    };
+
+    if is_changed {
+      changed.push(collection);
+    }
+  }
+
+  Ok(changed)
+}
+
+fn get_all(db: Db) -> Result<Vec<Collection>> {
+  collections::table
+    .filter(collections::smart_filter.is_not_null())
+    .select(Collection::as_select())
+    .load::<Collection>(db)
+    .map_err(Into::into)
+}
+
+fn to_ids(books: &[Book]) -> HashSet<i32> {
+  books.iter().map(|it| it.id).collect()
+}
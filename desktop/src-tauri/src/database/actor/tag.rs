@@ -0,0 +1,109 @@
+use crate::bail;
+use crate::database::actor::Db;
+use crate::database::model::{NewBookTag, NewTag, Tag};
+use crate::database::schema::book_tags;
+use crate::database::schema::tags::dsl::*;
+use crate::error::Error;
+use crate::result::Result;
+use diesel::prelude::*;
+use itertools::Itertools;
+
+pub(super) fn add_book(db: Db, tag_id: i32, book_id: i32) -> Result<()> {
+  let new = NewBookTag { book_id, tag_id };
+  diesel::insert_or_ignore_into(book_tags::table)
+    .values(&new)
+    .execute(db)?;
+
+  Ok(())
+}
+
+/// Create the tag, or return the existing one if there's already a tag with that name.
+pub(super) fn create(db: Db, tag_name: &str) -> Result<Tag> {
+  let tag_name = validate_name(tag_name)?;
+  let new = NewTag { name: tag_name.to_owned() };
+  diesel::insert_or_ignore_into(tags)
+    .values(&new)
+    .execute(db)?;
+
+  // Names are compared without regard to case.
+  tags
+    .filter(name.eq(tag_name))
+    .select(Tag::as_select())
+    .first::<Tag>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_all(db: Db) -> Result<Vec<Tag>> {
+  tags
+    .order_by(name)
+    .select(Tag::as_select())
+    .load::<Tag>(db)
+    .map_err(Into::into)
+}
+
+/// Ids of the books that have the tag.
+pub(super) fn get_books(db: Db, tag_id: i32) -> Result<Vec<i32>> {
+  book_tags::table
+    .filter(book_tags::tag_id.eq(tag_id))
+    .select(book_tags::book_id)
+    .load::<i32>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_by_book(db: Db, book_id: i32) -> Result<Vec<Tag>> {
+  tags
+    .inner_join(book_tags::table)
+    .filter(book_tags::book_id.eq(book_id))
+    .order_by(name)
+    .select(Tag::as_select())
+    .load::<Tag>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn remove(db: Db, tag_id: i32) -> Result<()> {
+  diesel::delete(tags.find(tag_id)).execute(db)?;
+  Ok(())
+}
+
+pub(super) fn remove_book(db: Db, tag_id: i32, book_id: i32) -> Result<()> {
+  diesel::delete(book_tags::table.find((book_id, tag_id))).execute(db)?;
+  Ok(())
+}
+
+pub(super) fn rename(db: Db, tag_id: i32, tag_name: &str) -> Result<Tag> {
+  let tag_name = validate_name(tag_name)?;
+  diesel::update(tags.find(tag_id))
+    .set(name.eq(tag_name))
+    .returning(Tag::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+/// Replace the tags of the book with those named, creating the ones that don't exist yet.
+pub(super) fn set_book_tags(db: Db, book_id: i32, names: &[String]) -> Result<Vec<Tag>> {
+  db.transaction::<_, Error, _>(|db| {
+    diesel::delete(book_tags::table.filter(book_tags::book_id.eq(book_id))).execute(db)?;
+
+    let names = names
+      .iter()
+      .map(|it| it.trim())
+      .filter(|it| !it.is_empty())
+      .unique_by(|it| it.to_lowercase());
+
+    for tag_name in names {
+      let tag = create(db, tag_name)?;
+      add_book(db, tag.id, book_id)?;
+    }
+
+    get_by_book(db, book_id)
+  })
+}
+
+fn validate_name(tag_name: &str) -> Result<&str> {
+  let tag_name = tag_name.trim();
+  if tag_name.is_empty() {
+    bail!(InvalidTagName);
+  }
+
+  Ok(tag_name)
+}
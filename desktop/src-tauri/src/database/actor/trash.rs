@@ -0,0 +1,55 @@
+use crate::database::actor::Db;
+use crate::database::model::{NewTrashedPage, TrashedPage};
+use crate::database::schema::trashed_pages::dsl::*;
+use crate::path::PathExt;
+use crate::result::Result;
+use diesel::prelude::*;
+use std::path::Path;
+
+pub(super) fn add(db: Db, page: &NewTrashedPage) -> Result<TrashedPage> {
+  diesel::insert_into(trashed_pages)
+    .values(page)
+    .returning(TrashedPage::as_returning())
+    .get_result(db)
+    .map_err(Into::into)
+}
+
+/// Every page in the trash, from the most recently deleted.
+pub(super) fn get_all(db: Db) -> Result<Vec<TrashedPage>> {
+  trashed_pages
+    .order_by(id.desc())
+    .select(TrashedPage::as_select())
+    .load::<TrashedPage>(db)
+    .map_err(Into::into)
+}
+
+pub(super) fn get_by_id(db: Db, trash_id: i32) -> Result<TrashedPage> {
+  trashed_pages
+    .find(trash_id)
+    .select(TrashedPage::as_select())
+    .first::<TrashedPage>(db)
+    .map_err(Into::into)
+}
+
+/// Page most recently deleted from the book, if any.
+pub(super) fn get_latest(db: Db, path: &Path) -> Result<Option<TrashedPage>> {
+  trashed_pages
+    .filter(book_path.eq(path.try_str()?))
+    .order_by(id.desc())
+    .select(TrashedPage::as_select())
+    .first::<TrashedPage>(db)
+    .optional()
+    .map_err(Into::into)
+}
+
+pub(super) fn remove(db: Db, trash_id: i32) -> Result<()> {
+  diesel::delete(trashed_pages.find(trash_id)).execute(db)?;
+  Ok(())
+}
+
+pub(super) fn remove_all(db: Db) -> Result<Vec<TrashedPage>> {
+  diesel::delete(trashed_pages)
+    .returning(TrashedPage::as_returning())
+    .get_results(db)
+    .map_err(Into::into)
+}
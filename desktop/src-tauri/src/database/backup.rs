@@ -0,0 +1,271 @@
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::manager::ManagerExt;
+use crate::path::{PathExt, PathResolverExt};
+use crate::result::Result;
+use crate::{bail, err};
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use diesel::sqlite::SqliteConnection;
+use itertools::Itertools;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+/// How many backups are kept. The oldest ones are removed as new ones are taken.
+const MAX_BACKUPS: usize = 5;
+/// Backups are taken when opening the database at most once in this period,
+/// unless there are migrations to run.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct DatabaseBackup {
+  pub path: PathBuf,
+  /// In UTC, with the same format as `added_at`.
+  pub created_at: String,
+  pub size: u64,
+}
+
+#[derive(QueryableByName)]
+struct IntegrityCheck {
+  #[diesel(sql_type = Text)]
+  integrity_check: String,
+}
+
+/// Backups of the database, from the most recent.
+pub fn get_all(app: &AppHandle) -> Result<Vec<DatabaseBackup>> {
+  let path = app.path().database()?;
+  let dir = app.path().database_backup_dir()?;
+  find(&path, &dir)
+}
+
+/// Replace the database with the backup, restarting the app to open it.
+/// The current database is kept as a backup, so the restore itself can be undone.
+pub async fn restore(app: &AppHandle, backup: &Path) -> Result<()> {
+  use tauri_plugin_manatsu::AppHandleExt as _;
+  use tauri_plugin_window_state::{AppHandleExt as _, StateFlags};
+
+  let path = app.path().database()?;
+  stage_restore(&path, backup)?;
+
+  let _ = app.save_window_state(StateFlags::all());
+  let _ = app.write_logs_to_disk();
+
+  restart(app).await;
+  Ok(())
+}
+
+pub async fn restore_with_dialog(app: &AppHandle) -> Result<()> {
+  use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .file()
+    .add_filter("Database", &["db"])
+    .set_directory(app.path().database_backup_dir()?)
+    .pick_file(move |response| {
+      let _ = tx.send(response);
+    });
+
+  let Some(response) = rx.await? else {
+    return Ok(());
+  };
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message("The library will be replaced by the backup, and Kotori will restart.")
+    .title("Restore backup")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Restore")
+    .cancel_button_label("Cancel")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if rx.await? {
+    restore(app, &response.path).await?;
+  }
+
+  Ok(())
+}
+
+/// Kotori will crash in dev mode after restarting.
+/// Unlike exiting, restarting doesn't give a chance to flush the metadata waiting to be written.
+async fn restart(app: &AppHandle) {
+  app.book_handle().flush().await;
+  app.restart();
+}
+
+/// Run SQLite's own integrity check, failing with whatever problems it found.
+pub(super) fn check_integrity(db: &mut SqliteConnection) -> Result<()> {
+  let problems = diesel::sql_query("PRAGMA integrity_check")
+    .load::<IntegrityCheck>(db)?
+    .into_iter()
+    .map(|it| it.integrity_check)
+    .filter(|it| it != "ok")
+    .collect_vec();
+
+  if !problems.is_empty() {
+    bail!(CorruptDatabase, "{}", problems.join("; "));
+  }
+
+  Ok(())
+}
+
+/// Copy the database to a new file in the backup directory, removing the oldest backups.
+///
+/// `VACUUM INTO` reads the whole database in a single transaction,
+/// so the copy is consistent even if it's taken while the app is running.
+pub(super) fn create(
+  db: &mut SqliteConnection,
+  database: &Path,
+  dir: &Path,
+) -> Result<DatabaseBackup> {
+  fs::create_dir_all(dir)?;
+  let path = next_path(database, dir)?;
+
+  // It refuses to overwrite an existing file, as would happen with two backups in the same second.
+  if path.try_exists()? {
+    fs::remove_file(&path)?;
+  }
+
+  diesel::sql_query("VACUUM INTO ?")
+    .bind::<Text, _>(path.try_str()?)
+    .execute(db)?;
+
+  #[cfg(feature = "tracing")]
+  tracing::info!("database backed up to {}", path.display());
+
+  for old in find(database, dir)?.into_iter().skip(MAX_BACKUPS) {
+    fs::remove_file(old.path)?;
+  }
+
+  find(database, dir)?
+    .into_iter()
+    .find(|it| it.path == path)
+    .ok_or_else(|| err!(InvalidPath, "{}", path.display()))
+}
+
+/// Backups of the database in the directory, from the most recent.
+pub(super) fn find(database: &Path, dir: &Path) -> Result<Vec<DatabaseBackup>> {
+  if !dir.try_exists()? {
+    return Ok(Vec::new());
+  }
+
+  let prefix = format!("{}-", stem(database)?);
+  let mut backups = Vec::new();
+  for entry in fs::read_dir(dir)?.flatten() {
+    let path = entry.path();
+    let Some(created_at) = path
+      .file_name()
+      .and_then(|it| it.to_str())
+      .and_then(|it| it.strip_prefix(&prefix))
+      .and_then(|it| it.strip_suffix(".db"))
+      .and_then(|it| NaiveDateTime::parse_from_str(it, TIMESTAMP_FORMAT).ok())
+    else {
+      continue;
+    };
+
+    backups.push(DatabaseBackup {
+      size: entry.metadata()?.len(),
+      created_at: created_at.format(ADDED_AT_FORMAT).to_string(),
+      path,
+    });
+  }
+
+  // The format sorts the same way as the dates it represents.
+  backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+  Ok(backups)
+}
+
+/// Whether enough time has passed since the most recent backup to take another one.
+pub(super) fn is_due(database: &Path, dir: &Path) -> Result<bool> {
+  let Some(latest) = find(database, dir)?.into_iter().next() else {
+    return Ok(true);
+  };
+
+  let elapsed = fs::metadata(latest.path)?
+    .modified()?
+    .elapsed()
+    .unwrap_or_default();
+
+  Ok(elapsed >= BACKUP_INTERVAL)
+}
+
+/// Check the backup, then leave a copy of it to replace the database the next time it's opened.
+pub(super) fn stage_restore(database: &Path, backup: &Path) -> Result<()> {
+  let mut connection = SqliteConnection::establish(backup.try_str()?)?;
+  check_integrity(&mut connection)?;
+  drop(connection);
+
+  fs::copy(backup, staged_path(database))?;
+  Ok(())
+}
+
+/// Replace the database with the backup left by [`stage_restore`], if there's one.
+/// The database being replaced is moved to `aside`, along with its journal.
+pub(super) fn apply_staged_restore(database: &Path, aside: &Path) -> Result<bool> {
+  let staged = staged_path(database);
+  if !staged.try_exists()? {
+    return Ok(false);
+  }
+
+  if database.try_exists()? {
+    fs::rename(database, aside)?;
+  }
+
+  // A leftover journal would otherwise be rolled back into the restored database.
+  let journal = journal_path(database);
+  if journal.try_exists()? {
+    fs::rename(&journal, journal_path(aside))?;
+  }
+
+  fs::rename(staged, database)?;
+
+  #[cfg(feature = "tracing")]
+  tracing::info!(
+    "database restored, previous one moved to {}",
+    aside.display()
+  );
+
+  Ok(true)
+}
+
+/// Where a new backup of the database would be saved.
+pub(super) fn next_path(database: &Path, dir: &Path) -> Result<PathBuf> {
+  let timestamp = Utc::now().format(TIMESTAMP_FORMAT);
+  Ok(dir.join(format!("{}-{timestamp}.db", stem(database)?)))
+}
+
+/// Where a database that can't be opened is kept, next to where it was.
+/// It isn't a backup, as there's no telling whether it can ever be restored.
+pub(super) fn broken_path(database: &Path) -> Result<PathBuf> {
+  let timestamp = Utc::now().format(TIMESTAMP_FORMAT);
+  let name = format!("{}-broken-{timestamp}.db", stem(database)?);
+  Ok(database.try_parent()?.join(name))
+}
+
+fn staged_path(database: &Path) -> PathBuf {
+  database.with_extension("db.restore")
+}
+
+fn journal_path(database: &Path) -> PathBuf {
+  let mut path = database.as_os_str().to_owned();
+  path.push("-journal");
+  PathBuf::from(path)
+}
+
+fn stem(database: &Path) -> Result<&str> {
+  database
+    .file_stem()
+    .and_then(|it| it.to_str())
+    .ok_or_else(|| err!(InvalidPath, "{}", database.display()))
+}
@@ -1,4 +1,5 @@
 use crate::book::Title;
+use crate::database::backup::DatabaseBackup;
 use crate::database::model::prelude::*;
 use crate::result::TxResult;
 use std::fmt;
@@ -8,6 +9,62 @@ use strum::Display;
 #[derive(Display)]
 #[strum(serialize_all = "snake_case")]
 pub enum Message {
+  AddToCollection {
+    collection_id: i32,
+    book_id: i32,
+    tx: TxResult<Collection>,
+  },
+  AddTagToBook {
+    tag_id: i32,
+    book_id: i32,
+    tx: TxResult<()>,
+  },
+  AddToTrash {
+    page: NewTrashedPage,
+    tx: TxResult<TrashedPage>,
+  },
+  AssignSeries {
+    book_id: i32,
+    name: String,
+    volume: Option<i32>,
+    chapter: Option<f64>,
+    tx: TxResult<SeriesBook>,
+  },
+  ClearSeries {
+    tx: TxResult<()>,
+  },
+  CreateBackup {
+    database: PathBuf,
+    dir: PathBuf,
+    tx: TxResult<DatabaseBackup>,
+  },
+  CreateCollection {
+    name: String,
+    tx: TxResult<Collection>,
+  },
+  CreateSmartCollection {
+    name: String,
+    filter: SmartFilter,
+    tx: TxResult<Collection>,
+  },
+  CreateTag {
+    name: String,
+    tx: TxResult<Tag>,
+  },
+  EndReadingSession {
+    session_id: i32,
+    pages_viewed: usize,
+    tx: TxResult<()>,
+  },
+  EmptyTrash {
+    tx: TxResult<Vec<TrashedPage>>,
+  },
+  ExpireSmartCollections {
+    tx: TxResult<Vec<Collection>>,
+  },
+  ExportLibrary {
+    tx: TxResult<LibraryBundle>,
+  },
   GetAllBooks {
     tx: TxResult<Vec<Book>>,
   },
@@ -17,6 +74,20 @@ pub enum Message {
   GetAllFolders {
     tx: TxResult<Vec<PathBuf>>,
   },
+  GetAllSeries {
+    tx: TxResult<Vec<(Series, Vec<Book>)>>,
+  },
+  GetAllTags {
+    tx: TxResult<Vec<Tag>>,
+  },
+  GetAllTrashedPages {
+    tx: TxResult<Vec<TrashedPage>>,
+  },
+  GetAdjacentInSeries {
+    book_id: i32,
+    step: isize,
+    tx: TxResult<Option<Book>>,
+  },
   GetBookById {
     book_id: i32,
     tx: TxResult<Book>,
@@ -25,6 +96,23 @@ pub enum Message {
     book_path: PathBuf,
     tx: TxResult<Book>,
   },
+  GetBooksInFolder {
+    folder: PathBuf,
+    tx: TxResult<Vec<Book>>,
+  },
+  GetBooksByHash {
+    book_hash: String,
+    tx: TxResult<Vec<Book>>,
+  },
+  GetBooksWithoutAddedAt {
+    tx: TxResult<Vec<Book>>,
+  },
+  GetBooksWithoutHash {
+    tx: TxResult<Vec<Book>>,
+  },
+  GetBooksWithoutSeries {
+    tx: TxResult<Vec<Book>>,
+  },
   GetBookCover {
     book_id: i32,
     tx: TxResult<String>,
@@ -33,10 +121,56 @@ pub enum Message {
     book_id: i32,
     tx: TxResult<PathBuf>,
   },
+  GetBookCollections {
+    book_id: i32,
+    tx: TxResult<Vec<Collection>>,
+  },
+  GetBookSeries {
+    book_id: i32,
+    tx: TxResult<Option<SeriesBook>>,
+  },
+  GetBookTags {
+    book_id: i32,
+    tx: TxResult<Vec<Tag>>,
+  },
   GetBookTitle {
     book_id: i32,
     tx: TxResult<Title>,
   },
+  GetCollectionBooks {
+    collection_id: i32,
+    tx: TxResult<Vec<Book>>,
+  },
+  GetDuplicateBooks {
+    tx: TxResult<Vec<Vec<Book>>>,
+  },
+  GetSeriesBooks {
+    series_id: i32,
+    tx: TxResult<Vec<Book>>,
+  },
+  GetReadingHistory {
+    book_id: i32,
+    tx: TxResult<Vec<ReadingSession>>,
+  },
+  GetRecentlyRead {
+    limit: usize,
+    tx: TxResult<Vec<Book>>,
+  },
+  GetLibraryFolders {
+    tx: TxResult<Vec<Folder>>,
+  },
+  GetLatestTrashedPage {
+    book_path: PathBuf,
+    tx: TxResult<Option<TrashedPage>>,
+  },
+  GetTagBooks {
+    tag_id: i32,
+    tx: TxResult<Vec<i32>>,
+  },
+  GetTrashedPage {
+    trash_id: i32,
+    tx: TxResult<TrashedPage>,
+  },
   HasAnyBook {
     tx: TxResult<bool>,
   },
@@ -47,13 +181,76 @@ pub enum Message {
     book_path: PathBuf,
     tx: TxResult<bool>,
   },
+  ImportLibrary {
+    bundle: LibraryBundle,
+    rewrites: Vec<PathRewrite>,
+    tx: TxResult<ImportedBundle>,
+  },
+  MoveBook {
+    book_id: i32,
+    from: PathBuf,
+    to: PathBuf,
+    tx: TxResult<Option<Book>>,
+  },
+  QueryBooks {
+    query: LibraryQuery,
+    tx: TxResult<BookPage>,
+  },
   RandomBook {
     tx: TxResult<Option<Book>>,
   },
+  RefreshSmartCollections {
+    book_id: i32,
+    tx: TxResult<Vec<Collection>>,
+  },
+  RelinkFolder {
+    folder_id: i32,
+    folder_path: PathBuf,
+    tx: TxResult<RelinkedFolder>,
+  },
   RemoveBook {
     book_id: i32,
     tx: TxResult<()>,
   },
+  RemoveCollection {
+    collection_id: i32,
+    tx: TxResult<()>,
+  },
+  RemoveEmptySeries {
+    tx: TxResult<()>,
+  },
+  RemoveFolder {
+    folder_id: i32,
+    tx: TxResult<Folder>,
+  },
+  RemoveFromCollection {
+    collection_id: i32,
+    book_id: i32,
+    tx: TxResult<Collection>,
+  },
+  RemoveFromTrash {
+    trash_id: i32,
+    tx: TxResult<()>,
+  },
+  RemoveTag {
+    tag_id: i32,
+    tx: TxResult<()>,
+  },
+  RemoveTagFromBook {
+    tag_id: i32,
+    book_id: i32,
+    tx: TxResult<()>,
+  },
+  RenameCollection {
+    collection_id: i32,
+    name: String,
+    tx: TxResult<Collection>,
+  },
+  RenameTag {
+    tag_id: i32,
+    name: String,
+    tx: TxResult<Tag>,
+  },
   SaveBook {
     book: NewBook,
     tx: TxResult<Book>,
@@ -62,11 +259,50 @@ pub enum Message {
     folders: Vec<NewFolder>,
     tx: TxResult<()>,
   },
+  SearchBooks {
+    query: String,
+    limit: Option<usize>,
+    tx: TxResult<Vec<Book>>,
+  },
+  SetBookTags {
+    book_id: i32,
+    names: Vec<String>,
+    tx: TxResult<Vec<Tag>>,
+  },
+  StartReadingSession {
+    session: NewReadingSession,
+    tx: TxResult<ReadingSession>,
+  },
+  UnassignSeries {
+    book_id: i32,
+    tx: TxResult<()>,
+  },
+  UpdateBookAddedAt {
+    book_id: i32,
+    added_at: String,
+    tx: TxResult<()>,
+  },
   UpdateBookCover {
     book_id: i32,
     cover: String,
     tx: TxResult<Book>,
   },
+  UpdateBookHash {
+    book_id: i32,
+    book_hash: String,
+    tx: TxResult<Book>,
+  },
+  UpdateBookPath {
+    book_id: i32,
+    book_path: PathBuf,
+    tx: TxResult<Book>,
+  },
+  UpdateBookProgress {
+    book_id: i32,
+    page_index: usize,
+    page_name: String,
+    tx: TxResult<Book>,
+  },
   UpdateBookRating {
     book_id: i32,
     rating: u8,
@@ -77,6 +313,16 @@ pub enum Message {
     read: bool,
     tx: TxResult<Book>,
   },
+  UpdateFolderExclude {
+    folder_id: i32,
+    exclude: Exclude,
+    tx: TxResult<Folder>,
+  },
+  UpdateSmartCollection {
+    collection_id: i32,
+    filter: SmartFilter,
+    tx: TxResult<Collection>,
+  },
 
   #[cfg(feature = "devtools")]
   RemoveAllBooks {
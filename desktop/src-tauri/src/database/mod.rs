@@ -1,16 +1,20 @@
 mod actor;
+pub mod backup;
 mod message;
 pub mod model;
 mod schema;
 
-use crate::book::{ActiveBook, Title};
+use crate::book::{ActiveBook, SeriesInfo, Title};
+use crate::database::backup::DatabaseBackup;
 use crate::database::model::prelude::*;
+use crate::error::Error;
 use crate::event::Event;
 use crate::menu::AppMenu;
-use crate::path::PathExt;
-use crate::result::Result;
-use crate::send_tx;
+use crate::path::{PathExt, PathResolverExt};
+use crate::result::{Result, ResultExt as _};
+use crate::{err, send_tx};
 use actor::Actor;
+use diesel::connection::SimpleConnection;
 use diesel::prelude::*;
 use diesel::sqlite::SqliteConnection;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
@@ -30,19 +34,17 @@ pub struct DatabaseHandle {
 
 impl DatabaseHandle {
   pub fn new(app: &AppHandle) -> Result<Self> {
-    let path = app.path().app_local_data_dir()?;
-    fs::create_dir_all(&path)?;
+    let path = app.path().database()?;
+    let backup_dir = app.path().database_backup_dir()?;
+    fs::create_dir_all(&backup_dir)?;
 
-    #[cfg(feature = "devtools")]
-    let path = path.join("kotori-dev.db");
-    #[cfg(not(feature = "devtools"))]
-    let path = path.join("kotori.db");
+    // A backup chosen while the app was running only replaces the database now.
+    backup::apply_staged_restore(&path, &backup::next_path(&path, &backup_dir)?)?;
 
-    let database_url = path.try_str()?;
-    let mut connection = SqliteConnection::establish(database_url)?;
-    connection
-      .run_pending_migrations(MIGRATIONS)
-      .unwrap();
+    let connection = match open(&path, &backup_dir) {
+      Ok(connection) => connection,
+      Err(err) => recover(app, &path, &backup_dir, err)?,
+    };
 
     let (sender, receiver) = mpsc::channel();
     let mut actor = Actor::new(connection, receiver);
@@ -58,6 +60,77 @@ impl DatabaseHandle {
     Ok(Self { app, sender })
   }
 
+  pub async fn add_to_collection(&self, collection_id: i32, book_id: i32) -> Result<Collection> {
+    let collection = send_tx!(self, AddToCollection { collection_id, book_id })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
+  pub async fn add_tag_to_book(&self, tag_id: i32, book_id: i32) -> Result<()> {
+    send_tx!(self, AddTagToBook { tag_id, book_id })?;
+    self.save_book_as_metadata(book_id).await
+  }
+
+  pub async fn add_to_trash(&self, page: NewTrashedPage) -> Result<TrashedPage> {
+    send_tx!(self, AddToTrash { page })
+  }
+
+  pub async fn assign_series(&self, book_id: i32, info: SeriesInfo) -> Result<SeriesBook> {
+    let SeriesInfo { name, volume, chapter } = info;
+    send_tx!(self, AssignSeries { book_id, name, volume, chapter })
+  }
+
+  /// Forget every series. Books are left untouched.
+  pub async fn clear_series(&self) -> Result<()> {
+    send_tx!(self, ClearSeries {})
+  }
+
+  /// Back up the database right away, regardless of when the last backup was taken.
+  pub async fn create_backup(&self) -> Result<DatabaseBackup> {
+    let database = self.app.path().database()?;
+    let dir = self.app.path().database_backup_dir()?;
+    send_tx!(self, CreateBackup { database, dir })
+  }
+
+  pub async fn create_collection(&self, name: &str) -> Result<Collection> {
+    let name = name.to_owned();
+    let collection = send_tx!(self, CreateCollection { name })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
+  pub async fn create_smart_collection(
+    &self,
+    name: &str,
+    filter: SmartFilter,
+  ) -> Result<Collection> {
+    let name = name.to_owned();
+    let collection = send_tx!(self, CreateSmartCollection { name, filter })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
+  /// Create the tag, or get the existing one with the same name.
+  pub async fn create_tag(&self, name: &str) -> Result<Tag> {
+    let name = name.to_owned();
+    send_tx!(self, CreateTag { name })
+  }
+
+  /// Close the session, recording how many pages were viewed during it.
+  pub async fn end_reading_session(&self, session_id: i32, pages_viewed: usize) -> Result<()> {
+    send_tx!(self, EndReadingSession { session_id, pages_viewed })
+  }
+
+  /// Forget every page in the trash, returning them so that their files can be removed.
+  pub async fn empty_trash(&self) -> Result<Vec<TrashedPage>> {
+    send_tx!(self, EmptyTrash {})
+  }
+
+  /// Everything the library knows about its books, ready to be written to a file.
+  pub async fn export_library(&self) -> Result<LibraryBundle> {
+    send_tx!(self, ExportLibrary {})
+  }
+
   pub async fn get_all_books(&self) -> Result<Vec<Book>> {
     send_tx!(self, GetAllBooks {})
   }
@@ -70,6 +143,20 @@ impl DatabaseHandle {
     send_tx!(self, GetAllFolders {})
   }
 
+  /// Every series, along with its books in reading order.
+  pub async fn get_all_series(&self) -> Result<Vec<(Series, Vec<Book>)>> {
+    send_tx!(self, GetAllSeries {})
+  }
+
+  pub async fn get_all_tags(&self) -> Result<Vec<Tag>> {
+    send_tx!(self, GetAllTags {})
+  }
+
+  /// Pages in the trash, from the most recently deleted.
+  pub async fn get_all_trashed_pages(&self) -> Result<Vec<TrashedPage>> {
+    send_tx!(self, GetAllTrashedPages {})
+  }
+
   pub async fn get_book_by_id(&self, book_id: i32) -> Result<Book> {
     send_tx!(self, GetBookById { book_id })
   }
@@ -79,6 +166,30 @@ impl DatabaseHandle {
     send_tx!(self, GetBookByPath { book_path })
   }
 
+  /// Books at the path or anywhere below it.
+  pub async fn get_books_in_folder(&self, folder: impl AsRef<Path>) -> Result<Vec<Book>> {
+    let folder = folder.as_ref().to_owned();
+    send_tx!(self, GetBooksInFolder { folder })
+  }
+
+  /// Books with the given hash, which are either duplicates or the same book under another path.
+  pub async fn get_books_by_hash(&self, book_hash: &str) -> Result<Vec<Book>> {
+    let book_hash = book_hash.to_owned();
+    send_tx!(self, GetBooksByHash { book_hash })
+  }
+
+  pub async fn get_books_without_added_at(&self) -> Result<Vec<Book>> {
+    send_tx!(self, GetBooksWithoutAddedAt {})
+  }
+
+  pub async fn get_books_without_hash(&self) -> Result<Vec<Book>> {
+    send_tx!(self, GetBooksWithoutHash {})
+  }
+
+  pub async fn get_books_without_series(&self) -> Result<Vec<Book>> {
+    send_tx!(self, GetBooksWithoutSeries {})
+  }
+
   pub async fn get_book_cover(&self, book_id: i32) -> Result<String> {
     send_tx!(self, GetBookCover { book_id })
   }
@@ -87,10 +198,82 @@ impl DatabaseHandle {
     send_tx!(self, GetBookPath { book_id })
   }
 
+  /// Collections that contain the book.
+  pub async fn get_book_collections(&self, book_id: i32) -> Result<Vec<Collection>> {
+    send_tx!(self, GetBookCollections { book_id })
+  }
+
+  /// Where the book stands within its series, if it belongs to one.
+  pub async fn get_book_series(&self, book_id: i32) -> Result<Option<SeriesBook>> {
+    send_tx!(self, GetBookSeries { book_id })
+  }
+
+  pub async fn get_book_tags(&self, book_id: i32) -> Result<Vec<Tag>> {
+    send_tx!(self, GetBookTags { book_id })
+  }
+
   pub async fn get_book_title(&self, book_id: i32) -> Result<Title> {
     send_tx!(self, GetBookTitle { book_id })
   }
 
+  pub async fn get_collection_books(&self, collection_id: i32) -> Result<Vec<Book>> {
+    send_tx!(self, GetCollectionBooks { collection_id })
+  }
+
+  /// Groups of books that share the same hash.
+  pub async fn get_duplicate_books(&self) -> Result<Vec<Vec<Book>>> {
+    send_tx!(self, GetDuplicateBooks {})
+  }
+
+  /// Books of the series, in reading order.
+  pub async fn get_series_books(&self, series_id: i32) -> Result<Vec<Book>> {
+    send_tx!(self, GetSeriesBooks { series_id })
+  }
+
+  /// Book that follows this one in its series, if any.
+  pub async fn get_next_in_series(&self, book_id: i32) -> Result<Option<Book>> {
+    let step = 1;
+    send_tx!(self, GetAdjacentInSeries { book_id, step })
+  }
+
+  /// Book that precedes this one in its series, if any.
+  pub async fn get_previous_in_series(&self, book_id: i32) -> Result<Option<Book>> {
+    let step = -1;
+    send_tx!(self, GetAdjacentInSeries { book_id, step })
+  }
+
+  /// Reading sessions of the book, from the most recent.
+  pub async fn get_reading_history(&self, book_id: i32) -> Result<Vec<ReadingSession>> {
+    send_tx!(self, GetReadingHistory { book_id })
+  }
+
+  /// Books read most recently, without repetition.
+  pub async fn get_recently_read(&self, limit: usize) -> Result<Vec<Book>> {
+    send_tx!(self, GetRecentlyRead { limit })
+  }
+
+  /// Folders in the library, along with their exclude patterns.
+  pub async fn get_library_folders(&self) -> Result<Vec<Folder>> {
+    send_tx!(self, GetLibraryFolders {})
+  }
+
+  /// Page most recently deleted from the book, if it's still in the trash.
+  pub async fn get_latest_trashed_page(
+    &self,
+    book_path: impl AsRef<Path>,
+  ) -> Result<Option<TrashedPage>> {
+    let book_path = book_path.as_ref().to_owned();
+    send_tx!(self, GetLatestTrashedPage { book_path })
+  }
+
+  pub async fn get_tag_books(&self, tag_id: i32) -> Result<Vec<i32>> {
+    send_tx!(self, GetTagBooks { tag_id })
+  }
+
+  pub async fn get_trashed_page(&self, trash_id: i32) -> Result<TrashedPage> {
+    send_tx!(self, GetTrashedPage { trash_id })
+  }
+
   pub async fn has_any_book(&self) -> Result<bool> {
     send_tx!(self, HasAnyBook {})
   }
@@ -104,6 +287,40 @@ impl DatabaseHandle {
     send_tx!(self, HasBookPath { book_path })
   }
 
+  /// Merge the bundle into the library, rewriting its paths along the way.
+  pub async fn import_library(
+    &self,
+    bundle: LibraryBundle,
+    rewrites: Vec<PathRewrite>,
+  ) -> Result<ImportedBundle> {
+    let imported = send_tx!(self, ImportLibrary { bundle, rewrites })?;
+    AppMenu::spawn_update(&self.app);
+
+    for collection in self.get_all_collections().await? {
+      Event::CollectionUpdated(&collection).emit(&self.app)?;
+    }
+
+    Ok(imported)
+  }
+
+  /// Point the book to a new path, unless it's no longer at `from`.
+  /// Returns `None` in that case, as something else already moved it.
+  pub async fn move_book(&self, book_id: i32, from: &Path, to: &Path) -> Result<Option<Book>> {
+    let from = from.to_owned();
+    let to = to.to_owned();
+    let book = send_tx!(self, MoveBook { book_id, from, to })?;
+    if book.is_some() {
+      self.refresh_smart_collections(book_id).await?;
+    }
+
+    Ok(book)
+  }
+
+  /// A page of books, sorted and filtered as the query asks.
+  pub async fn query_books(&self, query: LibraryQuery) -> Result<BookPage> {
+    send_tx!(self, QueryBooks { query })
+  }
+
   pub async fn random_book(&self) -> Result<Option<Book>> {
     send_tx!(self, RandomBook {})
   }
@@ -120,18 +337,132 @@ impl DatabaseHandle {
     send_tx!(self, RemoveAllFolders {})
   }
 
+  /// Evaluate again the smart collections relative to the current time, notifying the ones that changed.
+  pub async fn expire_smart_collections(&self) -> Result<()> {
+    let changed = send_tx!(self, ExpireSmartCollections {})?;
+    for collection in &changed {
+      Event::CollectionUpdated(collection).emit(&self.app)?;
+    }
+
+    Ok(())
+  }
+
+  /// Check whether the book entered or left any smart collection, notifying the ones that changed.
+  async fn refresh_smart_collections(&self, book_id: i32) -> Result<()> {
+    let changed = send_tx!(self, RefreshSmartCollections { book_id })?;
+    for collection in &changed {
+      Event::CollectionUpdated(collection).emit(&self.app)?;
+    }
+
+    Ok(())
+  }
+
+  /// Point the folder and everything below it to a new path, keeping the books as they are.
+  pub async fn relink_folder(&self, folder_id: i32, folder_path: &Path) -> Result<RelinkedFolder> {
+    let folder_path = folder_path.to_owned();
+    let relinked = send_tx!(self, RelinkFolder { folder_id, folder_path })?;
+    AppMenu::spawn_update(&self.app);
+
+    for book in &relinked.books {
+      self.refresh_smart_collections(book.id).await?;
+    }
+
+    for book_id in &relinked.replaced {
+      self.refresh_smart_collections(*book_id).await?;
+    }
+
+    Ok(relinked)
+  }
+
   pub async fn remove_book(&self, book_id: i32) -> Result<()> {
     send_tx!(self, RemoveBook { book_id })?;
     AppMenu::spawn_update(&self.app);
+    self.refresh_smart_collections(book_id).await
+  }
+
+  pub async fn remove_collection(&self, collection_id: i32) -> Result<()> {
+    send_tx!(self, RemoveCollection { collection_id })?;
+    Event::CollectionRemoved(collection_id).emit(&self.app)
+  }
+
+  /// Remove the series left without any book.
+  pub async fn remove_empty_series(&self) -> Result<()> {
+    send_tx!(self, RemoveEmptySeries {})
+  }
+
+  /// Remove the folder, but not the books in it.
+  pub async fn remove_folder(&self, folder_id: i32) -> Result<Folder> {
+    let folder = send_tx!(self, RemoveFolder { folder_id })?;
+    AppMenu::spawn_update(&self.app);
+    Ok(folder)
+  }
+
+  pub async fn remove_from_collection(
+    &self,
+    collection_id: i32,
+    book_id: i32,
+  ) -> Result<Collection> {
+    let collection = send_tx!(self, RemoveFromCollection { collection_id, book_id })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
+  pub async fn remove_from_trash(&self, trash_id: i32) -> Result<()> {
+    send_tx!(self, RemoveFromTrash { trash_id })
+  }
+
+  /// Remove the tag from the library, and from every book that had it.
+  pub async fn remove_tag(&self, tag_id: i32) -> Result<()> {
+    // The books are looked up first, as they can't be found once the tag is gone.
+    let book_ids = self.get_tag_books(tag_id).await?;
+    send_tx!(self, RemoveTag { tag_id })?;
+    self.save_books_as_metadata(book_ids).await;
     Ok(())
   }
 
+  pub async fn remove_tag_from_book(&self, tag_id: i32, book_id: i32) -> Result<()> {
+    send_tx!(self, RemoveTagFromBook { tag_id, book_id })?;
+    self.save_book_as_metadata(book_id).await
+  }
+
+  pub async fn rename_collection(&self, collection_id: i32, name: &str) -> Result<Collection> {
+    let name = name.to_owned();
+    let collection = send_tx!(self, RenameCollection { collection_id, name })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
+  pub async fn rename_tag(&self, tag_id: i32, name: &str) -> Result<Tag> {
+    let name = name.to_owned();
+    let tag = send_tx!(self, RenameTag { tag_id, name })?;
+    let book_ids = self.get_tag_books(tag_id).await?;
+    self.save_books_as_metadata(book_ids).await;
+    Ok(tag)
+  }
+
   pub async fn save_book(&self, book: NewBook) -> Result<Book> {
     let book = send_tx!(self, SaveBook { book })?;
     AppMenu::spawn_update(&self.app);
+    self.refresh_smart_collections(book.id).await?;
     Ok(book)
   }
 
+  /// Write the book metadata, so that its file reflects what the library knows about it.
+  async fn save_book_as_metadata(&self, book_id: i32) -> Result<()> {
+    let book = self.get_book_by_id(book_id).await?;
+    book.save_as_metadata(&self.app).await
+  }
+
+  /// Failing to write the metadata of a book shouldn't prevent the others from being written.
+  async fn save_books_as_metadata(&self, book_ids: Vec<i32>) {
+    for book_id in book_ids {
+      self
+        .save_book_as_metadata(book_id)
+        .await
+        .into_err_log(&self.app);
+    }
+  }
+
   pub async fn save_folders<I>(&self, folders: I) -> Result<()>
   where
     I: IntoIterator<Item = NewFolder>,
@@ -142,6 +473,37 @@ impl DatabaseHandle {
     Ok(())
   }
 
+  /// Books matching the query, from the most relevant.
+  pub async fn search_books(&self, query: &str, limit: Option<usize>) -> Result<Vec<Book>> {
+    let query = query.to_owned();
+    send_tx!(self, SearchBooks { query, limit })
+  }
+
+  /// Replace the tags of the book, creating those that don't exist yet.
+  pub async fn set_book_tags<I>(&self, book_id: i32, names: I) -> Result<Vec<Tag>>
+  where
+    I: IntoIterator<Item = String>,
+  {
+    let names = names.into_iter().collect();
+    send_tx!(self, SetBookTags { book_id, names })
+  }
+
+  pub async fn start_reading_session(&self, book_id: i32) -> Result<ReadingSession> {
+    let session = NewReadingSession::new(book_id);
+    let session = send_tx!(self, StartReadingSession { session })?;
+    AppMenu::spawn_update(&self.app);
+    Ok(session)
+  }
+
+  pub async fn unassign_series(&self, book_id: i32) -> Result<()> {
+    send_tx!(self, UnassignSeries { book_id })
+  }
+
+  pub async fn update_book_added_at(&self, book_id: i32, added_at: &str) -> Result<()> {
+    let added_at = added_at.to_owned();
+    send_tx!(self, UpdateBookAddedAt { book_id, added_at })
+  }
+
   /// Set the specified page as the book cover, extracting it afterwards.
   pub async fn update_book_cover(&self, book_id: i32, cover: &str) -> Result<Book> {
     let cover = cover.to_owned();
@@ -154,21 +516,138 @@ impl DatabaseHandle {
     Ok(book)
   }
 
+  pub async fn update_book_hash(&self, book_id: i32, book_hash: &str) -> Result<Book> {
+    let book_hash = book_hash.to_owned();
+    send_tx!(self, UpdateBookHash { book_id, book_hash })
+  }
+
+  /// Point the book to a new path, keeping everything else about it.
+  pub async fn update_book_path(&self, book_id: i32, book_path: &Path) -> Result<Book> {
+    let book_path = book_path.to_owned();
+    let book = send_tx!(self, UpdateBookPath { book_id, book_path })?;
+    self.refresh_smart_collections(book_id).await?;
+    Ok(book)
+  }
+
+  /// Remember where the reader was left off, in the metadata too, so that it moves with the file.
+  /// This is called on every page turn, but writes to the same book are coalesced.
+  pub async fn update_book_progress(
+    &self,
+    book_id: i32,
+    page_index: usize,
+    page_name: &str,
+  ) -> Result<Book> {
+    let page_name = page_name.to_owned();
+    let book = send_tx!(self, UpdateBookProgress { book_id, page_index, page_name })?;
+
+    // Turning the page shouldn't fail because of the metadata.
+    book
+      .save_as_metadata(&self.app)
+      .await
+      .into_err_log(&self.app);
+
+    Ok(book)
+  }
+
   pub async fn update_book_rating(&self, book_id: i32, rating: u8) -> Result<Book> {
     let book = send_tx!(self, UpdateBookRating { book_id, rating })?;
 
     Event::RatingUpdated { id: book_id, rating }.emit(&self.app)?;
+    self.refresh_smart_collections(book_id).await?;
     book.save_as_metadata(&self.app).await?;
 
     Ok(book)
   }
 
+  pub async fn update_folder_exclude(&self, folder_id: i32, exclude: Exclude) -> Result<Folder> {
+    send_tx!(self, UpdateFolderExclude { folder_id, exclude })
+  }
+
+  pub async fn update_smart_collection(
+    &self,
+    collection_id: i32,
+    filter: SmartFilter,
+  ) -> Result<Collection> {
+    let collection = send_tx!(self, UpdateSmartCollection { collection_id, filter })?;
+    Event::CollectionUpdated(&collection).emit(&self.app)?;
+    Ok(collection)
+  }
+
   pub async fn update_book_read(&self, book_id: i32, read: bool) -> Result<Book> {
     let book = send_tx!(self, UpdateBookRead { book_id, read })?;
 
     Event::ReadUpdated { id: book_id, read }.emit(&self.app)?;
+    self.refresh_smart_collections(book_id).await?;
     book.save_as_metadata(&self.app).await?;
 
     Ok(book)
   }
 }
+
+/// Connect to the database, check its integrity and run the pending migrations.
+/// A backup is taken right before migrating, or when the last one is old enough.
+fn open(path: &Path, backup_dir: &Path) -> Result<SqliteConnection> {
+  let is_new = !path.try_exists()?;
+  let mut connection = SqliteConnection::establish(path.try_str()?)?;
+
+  // SQLite doesn't enforce foreign keys unless asked to.
+  connection.batch_execute("PRAGMA foreign_keys = ON;")?;
+  connection.register_collation("natural", natord::compare_ignore_case)?;
+  backup::check_integrity(&mut connection)?;
+
+  let has_pending = connection
+    .has_pending_migration(MIGRATIONS)
+    .map_err(|err| err!(Migration, "{err}"))?;
+
+  if !is_new && (has_pending || backup::is_due(path, backup_dir)?) {
+    backup::create(&mut connection, path, backup_dir)?;
+  }
+
+  connection
+    .run_pending_migrations(MIGRATIONS)
+    .map_err(|err| err!(Migration, "{err}"))?;
+
+  Ok(connection)
+}
+
+/// Offer to restore the most recent backup when the database can't be opened.
+/// Otherwise, or if there's no backup at all, the error is returned as is.
+fn recover(
+  app: &AppHandle,
+  path: &Path,
+  backup_dir: &Path,
+  error: Error,
+) -> Result<SqliteConnection> {
+  use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+  #[cfg(feature = "tracing")]
+  tracing::error!("failed to open database: {error}");
+
+  let Some(latest) = backup::find(path, backup_dir)?.into_iter().next() else {
+    return Err(error);
+  };
+
+  let message = format!(
+    "The library could not be opened: {error}\n\nRestore the backup from {} (UTC)?",
+    latest.created_at
+  );
+
+  // The app hasn't started yet, so there's nothing else to do while waiting.
+  let restore = app
+    .dialog()
+    .message(message)
+    .title("Restore backup")
+    .kind(MessageDialogKind::Error)
+    .ok_button_label("Restore")
+    .cancel_button_label("Quit")
+    .blocking_show();
+
+  if !restore {
+    return Err(error);
+  }
+
+  // The broken database is kept, in case it can still be salvaged.
+  backup::stage_restore(path, &latest.path)?;
+  backup::apply_staged_restore(path, &backup::broken_path(path)?)?;
+  open(path, backup_dir)
+}
@@ -1,12 +1,17 @@
-use crate::book::{Metadata, Title};
+use crate::book::{BookHandle, ComicInfo, Manga, Metadata, Progress, Title};
+use crate::database::model::BundleBook;
 use crate::manager::ManagerExt;
 use crate::path::PathExt;
 use crate::result::Result;
+use chrono::Utc;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
+/// Format of the `added_at` column, which sorts the same way as the dates it represents.
+pub const ADDED_AT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
 #[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
 #[diesel(table_name = crate::database::schema::books)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -19,6 +24,24 @@ pub struct Book {
   pub rating: i32,
   /// Whether the book has been read or not.
   pub read: bool,
+  pub series: Option<String>,
+  pub number: Option<String>,
+  pub volume: Option<i32>,
+  pub summary: Option<String>,
+  pub writer: Option<String>,
+  pub language: Option<String>,
+  pub manga: Option<bool>,
+  pub right_to_left: bool,
+  /// When the book was added to the library, in UTC.
+  /// Books added before this was tracked take when their file was last modified instead,
+  /// and those whose file couldn't be found don't have it until it can.
+  pub added_at: Option<String>,
+  /// Index of the page the reader was left on.
+  pub last_page_index: Option<i32>,
+  /// Name of the page the reader was left on, which survives pages being added or removed.
+  pub last_page_name: Option<String>,
+  /// Fingerprint of the pages, which stays the same wherever the file is moved to.
+  pub hash: Option<String>,
 }
 
 impl Book {
@@ -28,10 +51,18 @@ impl Book {
 
   pub async fn save_as_metadata(&self, app: &AppHandle) -> Result<()> {
     let path = Path::new(&self.path);
-    let metadata = Metadata::try_from(self)?;
+    let mut metadata = Metadata::try_from(self)?;
+    let tags = app
+      .database_handle()
+      .get_book_tags(self.id)
+      .await?;
+    metadata.tags = Some(tags.into_iter().map(|it| it.name).collect());
+
+    let comic_info = ComicInfo::is_write_enabled(app)?;
+    let backup = BookHandle::is_backup_enabled(app)?;
     app
       .book_handle()
-      .set_metadata(path, metadata)
+      .set_metadata(path, metadata, comic_info, backup)
       .await
   }
 }
@@ -44,15 +75,54 @@ pub struct NewBook {
   cover: String,
   rating: i32,
   read: bool,
+  series: Option<String>,
+  number: Option<String>,
+  volume: Option<i32>,
+  summary: Option<String>,
+  writer: Option<String>,
+  language: Option<String>,
+  manga: Option<bool>,
+  right_to_left: bool,
+  added_at: Option<String>,
+  last_page_index: Option<i32>,
+  last_page_name: Option<String>,
+  hash: Option<String>,
+}
+
+impl From<&BundleBook> for NewBook {
+  fn from(book: &BundleBook) -> Self {
+    Self {
+      path: book.path.clone(),
+      title: book.title.clone(),
+      cover: book.cover.clone(),
+      rating: book.rating,
+      read: book.read,
+      series: book.series.clone(),
+      number: book.number.clone(),
+      volume: book.volume,
+      summary: book.summary.clone(),
+      writer: book.writer.clone(),
+      language: book.language.clone(),
+      manga: book.manga,
+      right_to_left: book.right_to_left,
+      added_at: book.added_at.clone(),
+      last_page_index: book.last_page_index,
+      last_page_name: book.last_page_name.clone(),
+      hash: book.hash.clone(),
+    }
+  }
 }
 
 #[derive(Debug)]
 pub struct BookBuilder {
   path: PathBuf,
+  hash: Option<String>,
   title: Option<Title>,
   rating: Option<u8>,
   cover: Option<String>,
   read: Option<bool>,
+  progress: Option<Progress>,
+  comic_info: Option<ComicInfo>,
 }
 
 impl BookBuilder {
@@ -60,18 +130,41 @@ impl BookBuilder {
     let path = path.as_ref().to_path_buf();
     Self {
       path,
+      hash: None,
       title: None,
       rating: None,
       cover: None,
       read: None,
+      progress: None,
+      comic_info: None,
     }
   }
 
+  /// Use the `ComicInfo.xml` found in the book, if any.
+  /// Values from Kotori's own metadata take precedence over it.
+  pub fn comic_info(mut self, comic_info: ComicInfo) -> Self {
+    if self.title.is_none() {
+      self.title = comic_info.title.as_deref().map(Title::new);
+    }
+
+    if self.rating.is_none() {
+      self.rating = comic_info.rating();
+    }
+
+    self.comic_info = Some(comic_info);
+    self
+  }
+
   pub fn cover(mut self, cover: String) -> Self {
     self.cover = Some(cover);
     self
   }
 
+  pub fn hash(mut self, hash: String) -> Self {
+    self.hash = Some(hash);
+    self
+  }
+
   pub fn title(mut self, title: Title) -> Self {
     self.title = Some(title);
     self
@@ -94,6 +187,10 @@ impl BookBuilder {
       self.title = metadata.title.take();
     }
 
+    if metadata.progress.is_some() {
+      self.progress = metadata.progress.take();
+    }
+
     self
   }
 
@@ -104,14 +201,30 @@ impl BookBuilder {
       None => Title::try_from(&self.path)?.to_string(),
     };
 
-    let cover = match self.cover.take() {
-      Some(cover) => cover,
-      None => {
-        app
-          .book_handle()
-          .get_first_page_name(&self.path)
-          .await?
-      }
+    let handle = app.book_handle();
+    let front_cover = self
+      .comic_info
+      .as_ref()
+      .and_then(ComicInfo::front_cover);
+
+    let cover = match (self.cover.take(), front_cover) {
+      (Some(cover), _) => cover,
+      (None, Some(index)) => match handle.get_pages(&self.path).await?.get(&index) {
+        Some(cover) => cover.clone(),
+        None => handle.get_first_page_name(&self.path).await?,
+      },
+      (None, None) => handle.get_first_page_name(&self.path).await?,
+    };
+
+    let comic_info = self.comic_info.unwrap_or_default();
+    let manga = comic_info.manga;
+
+    let (last_page_index, last_page_name) = match self.progress {
+      Some(progress) => (
+        Some(i32::try_from(progress.page_index)?),
+        Some(progress.page_name),
+      ),
+      None => (None, None),
     };
 
     Ok(NewBook {
@@ -120,6 +233,18 @@ impl BookBuilder {
       cover,
       rating: self.rating.map_or(0, Into::into),
       read: self.read.unwrap_or(false),
+      series: comic_info.series,
+      number: comic_info.number,
+      volume: comic_info.volume,
+      summary: comic_info.summary,
+      writer: comic_info.writer,
+      language: comic_info.language,
+      manga: manga.and_then(Manga::is_manga),
+      right_to_left: manga.is_some_and(Manga::is_right_to_left),
+      added_at: Some(Utc::now().format(ADDED_AT_FORMAT).to_string()),
+      last_page_index,
+      last_page_name,
+      hash: self.hash,
     })
   }
 }
@@ -0,0 +1,166 @@
+use crate::database::model::{Book, Exclude, Folder, SmartFilter};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Everything the library knows about its books, in a file that can be taken to another machine.
+///
+/// Books are identified by their path, which can be rewritten when importing,
+/// and by their hash, which is used as a fallback when the path doesn't match any book.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryBundle {
+  pub version: u32,
+  /// In UTC, with the same format as `added_at`.
+  pub exported_at: String,
+  pub folders: Vec<BundleFolder>,
+  pub collections: Vec<BundleCollection>,
+  pub books: Vec<BundleBook>,
+}
+
+impl LibraryBundle {
+  /// Bumped whenever the format changes in a way older versions of Kotori can't read.
+  pub const VERSION: u32 = 1;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleFolder {
+  pub path: String,
+  pub exclude: Exclude,
+}
+
+impl From<Folder> for BundleFolder {
+  fn from(folder: Folder) -> Self {
+    Self {
+      path: folder.path,
+      exclude: folder.exclude,
+    }
+  }
+}
+
+/// Collections are matched by name, so the books of a regular one refer to it that way.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCollection {
+  pub name: String,
+  pub smart_filter: Option<SmartFilter>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleBook {
+  pub path: String,
+  pub title: String,
+  pub cover: String,
+  pub rating: i32,
+  pub read: bool,
+  pub series: Option<String>,
+  pub number: Option<String>,
+  pub volume: Option<i32>,
+  pub summary: Option<String>,
+  pub writer: Option<String>,
+  pub language: Option<String>,
+  pub manga: Option<bool>,
+  pub right_to_left: bool,
+  pub added_at: Option<String>,
+  pub last_page_index: Option<i32>,
+  pub last_page_name: Option<String>,
+  pub hash: Option<String>,
+  /// Names of the regular collections the book is in.
+  #[serde(default)]
+  pub collections: Vec<String>,
+  #[serde(default)]
+  pub tags: Vec<String>,
+}
+
+impl BundleBook {
+  pub fn from_model(book: Book, collections: Vec<String>, tags: Vec<String>) -> Self {
+    Self {
+      path: book.path,
+      title: book.title,
+      cover: book.cover,
+      rating: book.rating,
+      read: book.read,
+      series: book.series,
+      number: book.number,
+      volume: book.volume,
+      summary: book.summary,
+      writer: book.writer,
+      language: book.language,
+      manga: book.manga,
+      right_to_left: book.right_to_left,
+      added_at: book.added_at,
+      last_page_index: book.last_page_index,
+      last_page_name: book.last_page_name,
+      hash: book.hash,
+      collections,
+      tags,
+    }
+  }
+}
+
+/// Where the paths in the bundle should point to on this machine,
+/// such as from `D:\Manga` to `/home/user/manga`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathRewrite {
+  pub from: String,
+  pub to: String,
+}
+
+impl PathRewrite {
+  /// Apply the rewrite with the longest matching prefix, if any, leaving the path as is otherwise.
+  ///
+  /// The bundle may come from another platform, so paths are compared as strings,
+  /// component by component, with both `/` and `\` taken as separators.
+  /// Otherwise, `D:\Manga\Book.cbz` would be a single component on Unix.
+  pub fn apply(rewrites: &[Self], path: &str) -> String {
+    let parts = components(path).collect_vec();
+    let rewrite = rewrites
+      .iter()
+      .filter_map(|it| {
+        let from = components(&it.from).collect_vec();
+        let is_prefix = !from.is_empty() && parts.starts_with(&from);
+        is_prefix.then_some((it, from.len()))
+      })
+      .max_by_key(|(_, len)| *len);
+
+    let Some((rewrite, len)) = rewrite else {
+      return path.to_owned();
+    };
+
+    // The rest of the path takes the separator of the destination.
+    let separator = if rewrite.to.contains('\\') && !rewrite.to.contains('/') {
+      "\\"
+    } else {
+      "/"
+    };
+
+    let to = rewrite.to.trim_end_matches(['/', '\\']);
+    let suffix = parts[len..].join(separator);
+    if suffix.is_empty() {
+      to.to_owned()
+    } else {
+      format!("{to}{separator}{suffix}")
+    }
+  }
+}
+
+fn components(path: &str) -> impl Iterator<Item = &str> {
+  path
+    .split(['/', '\\'])
+    .filter(|it| !it.is_empty())
+}
+
+/// Outcome of importing a bundle.
+#[derive(Debug, Default)]
+pub struct ImportedBundle {
+  /// Books that weren't in the library yet.
+  pub added: Vec<Book>,
+  /// Books already in the library, now with the rating, progress and so on from the bundle.
+  pub updated: Vec<Book>,
+  /// Folders that weren't in the library yet.
+  pub folders: Vec<Folder>,
+  /// Books that weren't in the library, left out because their file couldn't be found.
+  pub skipped: usize,
+}
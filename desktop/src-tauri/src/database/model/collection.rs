@@ -1,11 +1,126 @@
+use crate::bail;
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::result::Result;
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
 use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::{Sqlite, SqliteValue};
+use globset::{GlobBuilder, GlobMatcher};
 use serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Selectable, Debug, Serialize, Deserialize)]
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
 #[diesel(table_name = crate::database::schema::collections)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct Collection {
   pub id: i32,
   pub name: String,
+  /// Smart collections have no members of their own.
+  /// Instead, they contain every book matching the filter.
+  pub smart_filter: Option<SmartFilter>,
+}
+
+impl Collection {
+  pub fn is_smart(&self) -> bool {
+    self.smart_filter.is_some()
+  }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::collections)]
+pub struct NewCollection {
+  pub name: String,
+  pub smart_filter: Option<SmartFilter>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::book_collections)]
+pub struct NewBookCollection {
+  pub book_id: i32,
+  pub collection_id: i32,
+}
+
+/// Conditions a book must meet to be part of a smart collection.
+/// Those left empty are ignored, so the default filter matches every book.
+#[derive(AsExpression, FromSqlRow, Clone, Debug, Default, Serialize, Deserialize)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartFilter {
+  pub min_rating: Option<u8>,
+  pub read: Option<bool>,
+  /// Only books inside this folder, at any depth.
+  pub folder: Option<String>,
+  /// Case-insensitive glob matched against the title.
+  pub title: Option<String>,
+  /// Date in the `YYYY-MM-DD` format.
+  pub added_after: Option<String>,
+  /// Relative to the moment the filter is evaluated, such as `7` for "added this week".
+  pub added_within_days: Option<u32>,
+}
+
+impl SmartFilter {
+  pub fn validate(&self) -> Result<()> {
+    if self.min_rating.is_some_and(|it| it > 5) {
+      bail!(InvalidRating);
+    }
+
+    self.title_matcher()?;
+    self.added_since()?;
+
+    Ok(())
+  }
+
+  /// Earliest value of `added_at` a matching book can have, if the filter has a date condition.
+  pub fn added_since(&self) -> Result<Option<String>> {
+    let after = match &self.added_after {
+      Some(date) => {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")?;
+        Some(date.and_time(NaiveTime::MIN))
+      }
+      None => None,
+    };
+
+    let within = self
+      .added_within_days
+      .and_then(|days| Utc::now().checked_sub_signed(Duration::days(days.into())))
+      .map(|it| it.naive_utc());
+
+    let since = after.into_iter().chain(within).max();
+    Ok(since.map(|it| it.format(ADDED_AT_FORMAT).to_string()))
+  }
+
+  /// Whether which books match depends on when the filter is evaluated.
+  pub fn is_relative(&self) -> bool {
+    self.added_within_days.is_some()
+  }
+
+  pub fn title_matcher(&self) -> Result<Option<GlobMatcher>> {
+    let Some(glob) = &self.title else {
+      return Ok(None);
+    };
+
+    let matcher = GlobBuilder::new(glob)
+      .case_insensitive(true)
+      .build()?
+      .compile_matcher();
+
+    Ok(Some(matcher))
+  }
+}
+
+impl FromSql<Text, Sqlite> for SmartFilter {
+  fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+    let json = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+    serde_json::from_str(&json).map_err(Into::into)
+  }
+}
+
+impl ToSql<Text, Sqlite> for SmartFilter {
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+    out.set_value(serde_json::to_string(self)?);
+    Ok(IsNull::No)
+  }
 }
@@ -1,13 +1,29 @@
+use crate::database::model::Book;
+use crate::result::Result;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
 use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::{Sqlite, SqliteValue};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Queryable, Selectable, Debug, Serialize, Deserialize)]
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
 #[diesel(table_name = crate::database::schema::folders)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 #[serde(rename_all(serialize = "camelCase"))]
 pub struct Folder {
   pub id: i32,
   pub path: String,
+  pub exclude: Exclude,
+}
+
+impl Folder {
+  pub fn exclude_matcher(&self) -> Result<ExcludeMatcher> {
+    self.exclude.matcher(&self.path)
+  }
 }
 
 #[derive(Insertable, Debug)]
@@ -15,3 +31,95 @@ pub struct Folder {
 pub struct NewFolder {
   pub path: String,
 }
+
+/// Outcome of pointing a folder to a new path.
+#[derive(Debug)]
+pub struct RelinkedFolder {
+  pub folder: Folder,
+  /// Books that now live under the new path.
+  pub books: Vec<Book>,
+  /// Books that were already saved under the new path, and have been replaced.
+  pub replaced: Vec<i32>,
+}
+
+/// Glob patterns for what should be skipped when searching the folder for books.
+///
+/// Patterns with a separator, such as `**/raw/**`, are matched against the path relative to the folder.
+/// Those without one, such as `__MACOSX`, are matched against the name of each entry along the way.
+#[derive(AsExpression, FromSqlRow, Clone, Debug, Default, Serialize, Deserialize)]
+#[diesel(sql_type = Text)]
+#[serde(transparent)]
+pub struct Exclude(pub Vec<String>);
+
+impl Exclude {
+  pub fn matcher(&self, root: impl AsRef<Path>) -> Result<ExcludeMatcher> {
+    let mut by_name = GlobSetBuilder::new();
+    let mut by_path = GlobSetBuilder::new();
+    for pattern in &self.0 {
+      let glob = GlobBuilder::new(pattern)
+        .case_insensitive(true)
+        .literal_separator(true)
+        .build()?;
+
+      if pattern.contains('/') {
+        by_path.add(glob);
+      } else {
+        by_name.add(glob);
+      }
+    }
+
+    Ok(ExcludeMatcher {
+      root: root.as_ref().to_owned(),
+      by_name: by_name.build()?,
+      by_path: by_path.build()?,
+    })
+  }
+}
+
+impl FromSql<Text, Sqlite> for Exclude {
+  fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+    let json = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+    serde_json::from_str(&json).map_err(Into::into)
+  }
+}
+
+impl ToSql<Text, Sqlite> for Exclude {
+  fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+    out.set_value(serde_json::to_string(self)?);
+    Ok(IsNull::No)
+  }
+}
+
+pub struct ExcludeMatcher {
+  root: PathBuf,
+  by_name: GlobSet,
+  by_path: GlobSet,
+}
+
+impl ExcludeMatcher {
+  /// Whether the path, which should be inside the folder, was excluded.
+  ///
+  /// Directories are also matched with a trailing separator,
+  /// as a pattern like `**/raw/**` would otherwise only exclude what's inside of `raw`.
+  pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+    let Ok(relative) = path.strip_prefix(&self.root) else {
+      return false;
+    };
+
+    if self.by_path.is_match(relative) {
+      return true;
+    }
+
+    if is_dir && !relative.as_os_str().is_empty() {
+      let mut dir = relative.as_os_str().to_owned();
+      dir.push("/");
+      if self.by_path.is_match(Path::new(&dir)) {
+        return true;
+      }
+    }
+
+    relative
+      .components()
+      .any(|it| self.by_name.is_match(it.as_os_str()))
+  }
+}
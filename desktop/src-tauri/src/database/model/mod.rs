@@ -1,11 +1,29 @@
 pub mod book;
+pub mod bundle;
 pub mod collection;
 pub mod folder;
+pub mod query;
+pub mod series;
+pub mod session;
+pub mod tag;
+pub mod trash;
 
 pub use book::{Book, NewBook};
-pub use collection::Collection;
-pub use folder::NewFolder;
+pub use bundle::{
+  BundleBook, BundleCollection, BundleFolder, ImportedBundle, LibraryBundle, PathRewrite,
+};
+pub use collection::{Collection, NewBookCollection, NewCollection, SmartFilter};
+pub use folder::{Exclude, ExcludeMatcher, Folder, NewFolder, RelinkedFolder};
+pub use query::{BookPage, Cursor, CursorValue, LibraryFilter, LibraryQuery, SortKey, SortOrder};
+pub use series::{NewSeries, Series, SeriesBook};
+pub use session::{NewReadingSession, ReadingSession};
+pub use tag::{NewBookTag, NewTag, Tag};
+pub use trash::{NewTrashedPage, TrashedPage};
 
 pub mod prelude {
-  pub use super::{Book, Collection, NewBook, NewFolder};
+  pub use super::{
+    Book, BookPage, Collection, Exclude, Folder, ImportedBundle, LibraryBundle, LibraryQuery,
+    NewBook, NewBookCollection, NewCollection, NewFolder, NewReadingSession, NewTrashedPage,
+    PathRewrite, ReadingSession, RelinkedFolder, Series, SeriesBook, SmartFilter, Tag, TrashedPage,
+  };
 }
@@ -0,0 +1,78 @@
+use crate::database::model::Book;
+use serde::{Deserialize, Serialize};
+
+/// How many books are returned at once when the query doesn't say.
+pub const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// A page of the library, sorted and filtered.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LibraryQuery {
+  pub sort: SortKey,
+  pub order: SortOrder,
+  pub filter: LibraryFilter,
+  /// Where the previous page ended, if this isn't the first one.
+  pub after: Option<Cursor>,
+  pub limit: Option<usize>,
+}
+
+impl LibraryQuery {
+  pub fn limit(&self) -> usize {
+    self.limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1)
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+  /// Natural order, so "Vol. 2" comes before "Vol. 10".
+  #[default]
+  Title,
+  Rating,
+  AddedAt,
+  LastRead,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+  #[default]
+  Asc,
+  Desc,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LibraryFilter {
+  pub read: Option<bool>,
+  pub min_rating: Option<u8>,
+  pub max_rating: Option<u8>,
+  /// Only books at or below this folder.
+  pub folder: Option<String>,
+  /// Only books in this collection, smart or not.
+  pub collection: Option<i32>,
+  /// Only books with every one of these tags.
+  pub tags: Vec<i32>,
+}
+
+/// Position of the last book in a page, from which the next one starts.
+/// It should be treated as opaque outside the database actor.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Cursor {
+  pub value: CursorValue,
+  pub id: i32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CursorValue {
+  Integer(i32),
+  Text(String),
+}
+
+#[derive(Debug)]
+pub struct BookPage {
+  pub books: Vec<Book>,
+  /// Where the next page starts, if there is one.
+  pub next: Option<Cursor>,
+}
@@ -0,0 +1,30 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
+#[diesel(table_name = crate::database::schema::series)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct Series {
+  pub id: i32,
+  pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::series)]
+pub struct NewSeries {
+  pub name: String,
+}
+
+/// Where a book stands within its series.
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone, Debug, Serialize, Deserialize)]
+#[diesel(table_name = crate::database::schema::series_books)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct SeriesBook {
+  pub book_id: i32,
+  pub series_id: i32,
+  pub volume: Option<i32>,
+  pub chapter: Option<f64>,
+}
@@ -0,0 +1,33 @@
+use crate::database::model::book::ADDED_AT_FORMAT;
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Time spent with a book open in a reader window.
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
+#[diesel(table_name = crate::database::schema::reading_sessions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ReadingSession {
+  pub id: i32,
+  pub book_id: i32,
+  /// In UTC, with the same format as `added_at`.
+  pub started_at: String,
+  /// Sessions cut short by a crash are never ended.
+  pub ended_at: Option<String>,
+  pub pages_viewed: i32,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::reading_sessions)]
+pub struct NewReadingSession {
+  pub book_id: i32,
+  pub started_at: String,
+}
+
+impl NewReadingSession {
+  pub fn new(book_id: i32) -> Self {
+    let started_at = Utc::now().format(ADDED_AT_FORMAT).to_string();
+    Self { book_id, started_at }
+  }
+}
@@ -0,0 +1,24 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
+#[diesel(table_name = crate::database::schema::tags)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct Tag {
+  pub id: i32,
+  pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::tags)]
+pub struct NewTag {
+  pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::book_tags)]
+pub struct NewBookTag {
+  pub book_id: i32,
+  pub tag_id: i32,
+}
@@ -0,0 +1,34 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Page deleted from a book, kept in the trash until it's either restored or purged.
+#[derive(Queryable, Selectable, Clone, Debug, Serialize, Deserialize)]
+#[diesel(table_name = crate::database::schema::trashed_pages)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct TrashedPage {
+  pub id: i32,
+  pub book_path: String,
+  pub page_name: String,
+  /// Position of the page in the book when it was deleted.
+  pub page_index: i32,
+  /// Whether the page was the cover of the book, which was then replaced by the first page.
+  pub was_cover: bool,
+  /// Name of the file holding the page in the trash directory.
+  pub file_name: String,
+  pub size: i64,
+  /// In UTC, with the same format as `added_at`.
+  pub deleted_at: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::trashed_pages)]
+pub struct NewTrashedPage {
+  pub book_path: String,
+  pub page_name: String,
+  pub page_index: i32,
+  pub was_cover: bool,
+  pub file_name: String,
+  pub size: i64,
+  pub deleted_at: String,
+}
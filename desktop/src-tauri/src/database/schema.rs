@@ -1,5 +1,45 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    /// Representation of the `book_collections` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    book_collections (book_id, collection_id) {
+        /// The `book_id` column of the `book_collections` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        book_id -> Integer,
+        /// The `collection_id` column of the `book_collections` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        collection_id -> Integer,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `book_tags` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    book_tags (book_id, tag_id) {
+        /// The `book_id` column of the `book_tags` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        book_id -> Integer,
+        /// The `tag_id` column of the `book_tags` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tag_id -> Integer,
+    }
+}
+
 diesel::table! {
     /// Representation of the `books` table.
     ///
@@ -41,6 +81,78 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         read -> Bool,
+        /// The `series` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        series -> Nullable<Text>,
+        /// The `number` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        number -> Nullable<Text>,
+        /// The `volume` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        volume -> Nullable<Integer>,
+        /// The `summary` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        summary -> Nullable<Text>,
+        /// The `writer` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        writer -> Nullable<Text>,
+        /// The `language` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        language -> Nullable<Text>,
+        /// The `manga` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Bool>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        manga -> Nullable<Bool>,
+        /// The `right_to_left` column of the `books` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        right_to_left -> Bool,
+        /// The `added_at` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        added_at -> Nullable<Text>,
+        /// The `last_page_index` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_page_index -> Nullable<Integer>,
+        /// The `last_page_name` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_page_name -> Nullable<Text>,
+        /// The `hash` column of the `books` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        hash -> Nullable<Text>,
     }
 }
 
@@ -61,6 +173,12 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         name -> Text,
+        /// The `smart_filter` column of the `collections` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        smart_filter -> Nullable<Text>,
     }
 }
 
@@ -81,11 +199,198 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         path -> Text,
+        /// The `exclude` column of the `folders` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        exclude -> Text,
     }
 }
 
+diesel::table! {
+    /// Representation of the `reading_sessions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    reading_sessions (id) {
+        /// The `id` column of the `reading_sessions` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `book_id` column of the `reading_sessions` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        book_id -> Integer,
+        /// The `started_at` column of the `reading_sessions` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        started_at -> Text,
+        /// The `ended_at` column of the `reading_sessions` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ended_at -> Nullable<Text>,
+        /// The `pages_viewed` column of the `reading_sessions` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        pages_viewed -> Integer,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `series` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    series (id) {
+        /// The `id` column of the `series` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `name` column of the `series` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `series_books` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    series_books (book_id) {
+        /// The `book_id` column of the `series_books` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        book_id -> Integer,
+        /// The `series_id` column of the `series_books` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        series_id -> Integer,
+        /// The `volume` column of the `series_books` table.
+        ///
+        /// Its SQL type is `Nullable<Integer>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        volume -> Nullable<Integer>,
+        /// The `chapter` column of the `series_books` table.
+        ///
+        /// Its SQL type is `Nullable<Double>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        chapter -> Nullable<Double>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `tags` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    tags (id) {
+        /// The `id` column of the `tags` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `name` column of the `tags` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `trashed_pages` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    trashed_pages (id) {
+        /// The `id` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Integer,
+        /// The `book_path` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        book_path -> Text,
+        /// The `page_name` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        page_name -> Text,
+        /// The `page_index` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Integer`.
+        ///
+        /// (Automatically generated by Diesel.)
+        page_index -> Integer,
+        /// The `was_cover` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        was_cover -> Bool,
+        /// The `file_name` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        file_name -> Text,
+        /// The `size` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `BigInt`.
+        ///
+        /// (Automatically generated by Diesel.)
+        size -> BigInt,
+        /// The `deleted_at` column of the `trashed_pages` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        deleted_at -> Text,
+    }
+}
+
+diesel::joinable!(book_collections -> books (book_id));
+diesel::joinable!(book_collections -> collections (collection_id));
+diesel::joinable!(book_tags -> books (book_id));
+diesel::joinable!(book_tags -> tags (tag_id));
+diesel::joinable!(reading_sessions -> books (book_id));
+diesel::joinable!(series_books -> books (book_id));
+diesel::joinable!(series_books -> series (series_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    book_collections,
+    book_tags,
     books,
     collections,
     folders,
+    reading_sessions,
+    series,
+    series_books,
+    tags,
+    trashed_pages,
 );
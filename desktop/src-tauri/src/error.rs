@@ -7,18 +7,38 @@ use serde::Serialize;
 pub enum Error {
   #[error("book not found")]
   BookNotFound,
+  #[error("database is corrupt: {0}")]
+  CorruptDatabase(String),
   #[error("book is empty")]
   EmptyBook,
   #[error("{0}")]
   InvalidBook(String),
+  #[error("collection name cannot be empty")]
+  InvalidCollectionName,
   #[error("invalid path: {0}")]
   InvalidPath(String),
   #[error("rating must be an integer between 0 and 5")]
   InvalidRating,
+  #[error("invalid series pattern: {0}")]
+  InvalidSeriesPattern(String),
+  #[error("tag name cannot be empty")]
+  InvalidTagName,
+  #[error("failed to migrate database: {0}")]
+  Migration(String),
+  #[error("only smart collections have a filter")]
+  NotSmartCollection,
+  #[error("page already exists: {0}")]
+  PageAlreadyExists(String),
   #[error("page not found: {0}")]
   PageNotFound(String),
+  #[error("book is read-only: {0}")]
+  ReadOnlyBook(String),
   #[error("reader window not found: {0}")]
   ReaderWindowNotFound(String),
+  #[error("books can't be manually added to or removed from a smart collection")]
+  SmartCollection,
+  #[error("unsupported library export version: {0}")]
+  UnsupportedBundleVersion(String),
   #[error("window menu not found")]
   WindowMenuNotFound,
 
@@ -39,8 +59,14 @@ pub enum Error {
   #[error(transparent)]
   Manatsu(#[from] tauri_plugin_manatsu::Error),
   #[error(transparent)]
+  Notify(#[from] notify::Error),
+  #[error(transparent)]
   Pinia(#[from] tauri_plugin_pinia::Error),
   #[error(transparent)]
+  Regex(#[from] regex::Error),
+  #[error(transparent)]
+  SevenZ(#[from] sevenz_rust::Error),
+  #[error(transparent)]
   Strum(#[from] strum::ParseError),
   #[error(transparent)]
   Tauri(#[from] tauri::Error),
@@ -59,10 +85,14 @@ pub enum Error {
   #[error(transparent)]
   TryFromInt(#[from] std::num::TryFromIntError),
   #[error(transparent)]
+  Unrar(#[from] unrar::error::UnrarError),
+  #[error(transparent)]
   WalkDir(#[from] walkdir::Error),
   #[error(transparent)]
   Unknown(#[from] anyhow::Error),
   #[error(transparent)]
+  Xml(#[from] quick_xml::Error),
+  #[error(transparent)]
   Zip(#[from] zip::result::ZipError),
 }
 
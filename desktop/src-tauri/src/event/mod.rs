@@ -1,6 +1,7 @@
 mod payload;
 
 use crate::book::LibraryBook;
+use crate::database::model::Collection;
 use crate::prelude::*;
 use crate::window::WindowKind;
 use serde::Serialize;
@@ -13,7 +14,10 @@ use tauri::Emitter;
 #[strum(serialize_all = "snake_case")]
 pub enum Event<'a> {
   BookAdded(&'a LibraryBook),
+  BookMissing(i32),
   BookRemoved(i32),
+  CollectionRemoved(i32),
+  CollectionUpdated(&'a Collection),
   CoverExtracted {
     id: i32,
     path: &'a Path,
@@ -41,7 +45,10 @@ pub enum Event<'a> {
 impl<'a> Event<'a> {
   #[cfg_attr(feature = "tracing", instrument(skip(app)))]
   pub fn emit(self, app: &AppHandle) -> Result<()> {
-    use payload::{BookRemoved, CoverExtracted, PageDeleted, RatingUpdated, ReadUpdated};
+    use payload::{
+      BookMissing, BookRemoved, CollectionRemoved, CoverExtracted, PageDeleted, RatingUpdated,
+      ReadUpdated,
+    };
 
     let event = self.as_ref();
 
@@ -59,7 +66,10 @@ impl<'a> Event<'a> {
 
     match self {
       Event::BookAdded(book) => to_main!(book),
+      Event::BookMissing(id) => to_main!(BookMissing { id }),
       Event::BookRemoved(id) => to_main!(BookRemoved { id }),
+      Event::CollectionRemoved(id) => to_main!(CollectionRemoved { id }),
+      Event::CollectionUpdated(collection) => to_main!(collection),
       Event::CoverExtracted { id, path } => to_main!(CoverExtracted::new(id, path)?),
       Event::PageDeleted { window_id, name } => to_reader!(window_id, PageDeleted::new(name)),
       Event::RatingUpdated { id, rating } => to_main!(RatingUpdated { id, rating }),
@@ -1,11 +1,21 @@
 use crate::prelude::*;
 use serde::Serialize;
 
+#[derive(Clone, Debug, Serialize)]
+pub struct BookMissing {
+  pub id: i32,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct BookRemoved {
   pub id: i32,
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct CollectionRemoved {
+  pub id: i32,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct CoverExtracted {
   pub id: i32,
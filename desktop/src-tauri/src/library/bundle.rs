@@ -0,0 +1,166 @@
+use super::{save_many, schedule_cover_extraction, series, walk_folder};
+use crate::bail;
+use crate::book::{LibraryBook, SeriesParser};
+use crate::database::model::{LibraryBundle, PathRewrite};
+use crate::event::Event;
+use crate::prelude::*;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tokio::fs;
+use tokio::sync::oneshot;
+
+/// Write the library to a file, so that it can be imported on another machine.
+pub async fn export(app: &AppHandle, path: &Path) -> Result<()> {
+  let bundle = app.database_handle().export_library().await?;
+  let json = serde_json::to_vec_pretty(&bundle)?;
+  fs::write(path, json).await?;
+
+  #[cfg(feature = "tracing")]
+  info!(
+    "{} books exported to {}",
+    bundle.books.len(),
+    path.display()
+  );
+
+  Ok(())
+}
+
+pub async fn export_with_dialog(app: &AppHandle) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .file()
+    .add_filter("JSON", &["json"])
+    .set_file_name("kotori-library.json")
+    .save_file(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if let Some(path) = rx.await? {
+    export(app, &path).await?;
+  }
+
+  Ok(())
+}
+
+/// Merge a previously exported library into this one.
+/// Paths starting with the `from` of a rewrite are moved under its `to`.
+///
+/// Returns how many books were left out because their file couldn't be found.
+pub async fn import(app: &AppHandle, path: &Path, rewrites: Vec<PathRewrite>) -> Result<usize> {
+  let json = fs::read(path).await?;
+  let bundle = serde_json::from_slice::<LibraryBundle>(&json)?;
+  if bundle.version > LibraryBundle::VERSION {
+    bail!(UnsupportedBundleVersion, "{}", bundle.version);
+  }
+
+  let imported = app
+    .database_handle()
+    .import_library(bundle, rewrites)
+    .await?;
+
+  #[cfg(feature = "tracing")]
+  info!(
+    added = imported.added.len(),
+    updated = imported.updated.len(),
+    folders = imported.folders.len(),
+    skipped = imported.skipped,
+    "library imported from {}",
+    path.display()
+  );
+
+  // The cover may have changed, so it's extracted again for every book that can be found.
+  let parser = SeriesParser::from_config(app)?;
+  let mut pending = Vec::new();
+  for model in imported.added {
+    series::assign(app, &parser, &model)
+      .await
+      .into_err_log(app);
+
+    // Books whose file can't be found are never added.
+    let book = LibraryBook::from_model(app, &model)?;
+    Event::BookAdded(&book).emit(app)?;
+    pending.push(model);
+  }
+
+  for model in imported.updated {
+    let mut book = LibraryBook::from_model(app, &model)?;
+    book.missing = matches!(fs::try_exists(&model.path).await, Ok(false));
+    Event::BookRemoved(book.id).emit(app)?;
+    Event::BookAdded(&book).emit(app)?;
+
+    if !book.missing {
+      model
+        .save_as_metadata(app)
+        .await
+        .into_err_log(app);
+      pending.push(model);
+    }
+  }
+
+  if !pending.is_empty() {
+    schedule_cover_extraction(app, pending);
+  }
+
+  // New folders may have books that weren't in the library when it was exported.
+  let mut books = Vec::new();
+  let watcher = app.library_watcher();
+  for folder in &imported.folders {
+    let path = Path::new(&folder.path);
+    watcher.watch(path).into_err_log(app);
+    walk_folder(&mut books, path, &[folder.exclude_matcher()?]);
+  }
+
+  if !books.is_empty() {
+    save_many(app, books).await?;
+  }
+
+  Ok(imported.skipped)
+}
+
+pub async fn import_with_dialog(app: &AppHandle, rewrites: Vec<PathRewrite>) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .file()
+    .add_filter("JSON", &["json"])
+    .pick_file(move |response| {
+      let _ = tx.send(response);
+    });
+
+  let Some(response) = rx.await? else {
+    return Ok(());
+  };
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message("Ratings, progress and collections from the file will be merged into the library.")
+    .title("Import library")
+    .kind(MessageDialogKind::Info)
+    .ok_button_label("Import")
+    .cancel_button_label("Cancel")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if !rx.await? {
+    return Ok(());
+  }
+
+  let skipped = import(app, &response.path, rewrites).await?;
+  if skipped > 0 {
+    let message = format!(
+      "{skipped} books were left out, as their file couldn't be found. \
+       They may have been moved, or the library may come from another computer."
+    );
+
+    app
+      .dialog()
+      .message(message)
+      .title("Import library")
+      .kind(MessageDialogKind::Warning)
+      .show(|_| {});
+  }
+
+  Ok(())
+}
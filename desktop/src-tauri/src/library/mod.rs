@@ -0,0 +1,885 @@
+pub mod bundle;
+pub mod series;
+mod watcher;
+
+use crate::book::{ActiveBook, LibraryBook, SeriesParser};
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::database::model::{
+  Book, Cursor, Exclude, ExcludeMatcher, Folder, LibraryQuery, NewFolder,
+};
+use crate::event::Event;
+use crate::prelude::*;
+use crate::utils::glob;
+use chrono::{DateTime, Utc};
+use future_iter::join_set::{IntoJoinSetBy, JoinSetFromIter};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tokio::fs;
+use tokio::sync::{oneshot, Semaphore};
+use walkdir::WalkDir;
+pub use watcher::{watch_folders, LibraryWatcher};
+
+const MAX_FILE_PERMITS: usize = 50;
+
+/// How often smart collections relative to the current time are evaluated again.
+const SMART_COLLECTION_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct LibraryPage {
+  pub books: Vec<LibraryBook>,
+  /// Where the next page starts, if there is one.
+  pub next: Option<Cursor>,
+}
+
+pub async fn add_with_dialog(app: &AppHandle) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  app.dialog().file().pick_folders(move |response| {
+    let _ = tx.send(response.unwrap_or_default());
+  });
+
+  let folders = rx.await?;
+  add_folders(app, folders).await
+}
+
+pub async fn add_folders<I>(app: &AppHandle, folders: I) -> Result<()>
+where
+  I: IntoIterator<Item = PathBuf>,
+{
+  let folders = folders.into_iter().collect_vec();
+  if folders.is_empty() {
+    return Ok(());
+  }
+
+  let mut books = Vec::new();
+  let library_folders = app.database_handle().get_all_folders().await?;
+  let mut current_folders = Vec::with_capacity(folders.len());
+
+  for folder in folders {
+    // There's no need to add folders contained in others that have already been saved.
+    if library_folders
+      .iter()
+      .chain(current_folders.iter())
+      .any(|it| folder.starts_with(it))
+    {
+      #[cfg(feature = "tracing")]
+      trace!(skip_folder = ?folder);
+      continue;
+    }
+
+    // New folders have nothing excluded yet.
+    walk_folder(&mut books, &folder, &[]);
+    current_folders.push(folder);
+  }
+
+  if !current_folders.is_empty() {
+    let watcher = app.library_watcher();
+    for folder in &current_folders {
+      watcher.watch(folder).into_err_log(app);
+    }
+
+    let folders = current_folders
+      .into_iter()
+      .filter_map(|path| path.try_string().ok())
+      .map(|path| NewFolder { path })
+      .collect_vec();
+
+    app
+      .database_handle()
+      .save_folders(folders)
+      .await?;
+  }
+
+  if !books.is_empty() {
+    save_many(app, books).await?;
+  }
+
+  Ok(())
+}
+
+async fn save_many<I>(app: &AppHandle, iter: I) -> Result<()>
+where
+  I: IntoIterator<Item = PathBuf>,
+{
+  #[cfg(feature = "tracing")]
+  let start = Instant::now();
+
+  let mut books = Vec::new();
+  let handle = app.database_handle();
+  for book in iter {
+    if !handle.has_book_path(&book).await? {
+      books.push(book);
+    }
+  }
+
+  if books.is_empty() {
+    return Ok(());
+  }
+
+  let parser = Arc::new(SeriesParser::from_config(app)?);
+  let semaphore = Arc::new(Semaphore::new(MAX_FILE_PERMITS));
+  let mut set = books.into_iter().join_set_by(|path| {
+    let app = app.clone();
+    let parser = Arc::clone(&parser);
+    let semaphore = Arc::clone(&semaphore);
+    async move {
+      let _permit = semaphore.acquire_owned().await?;
+
+      // A book that was renamed or moved keeps its place in the library.
+      let hash = app.book_handle().get_hash(&path).await?;
+      if relink_moved(&app, &parser, &path, &hash).await? {
+        return Ok(None);
+      }
+
+      save_with_hash(&app, &parser, &path, hash)
+        .await
+        .map(Some)
+    }
+  });
+
+  let mut models = Vec::with_capacity(set.len());
+  while let Some(result) = set.join_next().await {
+    match result? {
+      Ok(Some(model)) => models.push(model),
+      Ok(None) => {}
+      Err(err) => Err::<(), _>(err).into_err_log(app),
+    }
+  }
+
+  series::remove_empty(app).await.into_err_log(app);
+
+  if !models.is_empty() {
+    #[cfg(feature = "tracing")]
+    info!(
+      "{} books added to library in {:?}",
+      models.len(),
+      start.elapsed()
+    );
+
+    schedule_cover_extraction(app, models);
+  }
+
+  Ok(())
+}
+
+pub async fn save(app: &AppHandle, path: &Path) -> Result<Book> {
+  let parser = SeriesParser::from_config(app)?;
+  let hash = app.book_handle().get_hash(path).await?;
+  let model = save_with_hash(app, &parser, path, hash).await?;
+  series::remove_empty(app).await.into_err_log(app);
+  Ok(model)
+}
+
+/// Same as [`save`], for when the hash of the book is already known.
+/// Series left empty aren't removed, so that it's done only once when saving many books.
+async fn save_with_hash(
+  app: &AppHandle,
+  parser: &SeriesParser,
+  path: &Path,
+  hash: String,
+) -> Result<Book> {
+  let handle = app.book_handle();
+  let mut builder = Book::builder(path).hash(hash);
+  let mut tags = Vec::new();
+
+  // A malformed `ComicInfo.xml` left by some other tool shouldn't keep the book out of the library.
+  let comic_info = handle
+    .get_comic_info(path)
+    .await
+    .unwrap_or_else(|err| {
+      Err::<(), _>(err).into_err_log(app);
+      None
+    });
+
+  if let Some(comic_info) = comic_info {
+    tags = comic_info.tag_names();
+    builder = builder.comic_info(comic_info);
+  }
+
+  // Tags in the metadata were written by Kotori, so they are the most up to date.
+  if let Some(mut metadata) = handle.get_metadata(path).await? {
+    if let Some(metadata_tags) = metadata.tags.take() {
+      tags = metadata_tags;
+    }
+
+    builder = builder.metadata(metadata);
+  }
+
+  let new_book = builder.build(app).await?;
+  let database = app.database_handle();
+  let model = database.save_book(new_book).await?;
+  if !tags.is_empty() {
+    database
+      .set_book_tags(model.id, tags)
+      .await
+      .into_err_log(app);
+  }
+
+  series::assign(app, parser, &model)
+    .await
+    .into_err_log(app);
+
+  // We could already call `BookHandle::set_metadata` to write the metadata of the saved book,
+  // but that doesn't seem like a good idea. After all, the data would only be default values.
+  let book = LibraryBook::from_model(app, &model)?;
+  Event::BookAdded(&book).emit(app)?;
+
+  handle.close(path).await;
+
+  Ok(model)
+}
+
+/// Match a book that was renamed or moved on disk to the one already in the library, if any.
+/// Returns whether such a book was found.
+async fn relink_moved(
+  app: &AppHandle,
+  parser: &SeriesParser,
+  path: &Path,
+  hash: &str,
+) -> Result<bool> {
+  let database = app.database_handle();
+  let candidates = database.get_books_by_hash(hash).await?;
+  if candidates.is_empty() {
+    return Ok(false);
+  }
+
+  let folders = database.get_all_folders().await?;
+  for model in candidates {
+    // If the file is still there, this is a duplicate, not a move.
+    // And if its folder can't be reached, the file might not have gone anywhere,
+    // as when the drive it's on isn't connected.
+    let old_path = Path::new(&model.path);
+    if old_path.exists() || !is_root_available(&folders, old_path) {
+      continue;
+    }
+
+    // Many copies of the book may be saved at once, but only one of them can take its place.
+    let Some(model) = database
+      .move_book(model.id, old_path, path)
+      .await?
+    else {
+      continue;
+    };
+
+    #[cfg(feature = "tracing")]
+    debug!(move_book = model.id, from = %old_path.display(), to = %path.display());
+
+    series::assign(app, parser, &model)
+      .await
+      .into_err_log(app);
+
+    let book = LibraryBook::from_model(app, &model)?;
+    Event::BookRemoved(book.id).emit(app)?;
+    Event::BookAdded(&book).emit(app)?;
+
+    return Ok(true);
+  }
+
+  Ok(false)
+}
+
+/// Whether the library folder containing the path can be reached.
+fn is_root_available(folders: &[PathBuf], path: &Path) -> bool {
+  folders
+    .iter()
+    .filter(|it| path.starts_with(it))
+    .any(|it| it.exists())
+}
+
+/// Smart collections like "added this week" change as time goes by, not only when books do.
+/// This never returns, so it should be the last thing done by the task running it.
+pub async fn expire_smart_collections(app: &AppHandle) {
+  let mut interval = tokio::time::interval(SMART_COLLECTION_EXPIRY);
+  loop {
+    interval.tick().await;
+    app
+      .database_handle()
+      .expire_smart_collections()
+      .await
+      .into_err_log(app);
+  }
+}
+
+/// Hash the books that were saved before hashes were a thing.
+pub async fn hash_books(app: &AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  let start = Instant::now();
+
+  let database = app.database_handle();
+  let models = database.get_books_without_hash().await?;
+  if models.is_empty() {
+    return Ok(());
+  }
+
+  let semaphore = Arc::new(Semaphore::new(MAX_FILE_PERMITS / 5));
+  let mut set = models.into_iter().join_set_by(|model| {
+    let app = app.clone();
+    let semaphore = Arc::clone(&semaphore);
+    async move {
+      let _permit = semaphore.acquire_owned().await?;
+      let path = Path::new(&model.path);
+      if let Ok(false) = fs::try_exists(path).await {
+        return Ok(());
+      }
+
+      let handle = app.book_handle();
+      let hash = handle.get_hash(path).await?;
+      handle.close(path).await;
+
+      app
+        .database_handle()
+        .update_book_hash(model.id, &hash)
+        .await
+        .map(drop)
+    }
+  });
+
+  while let Some(result) = set.join_next().await {
+    result?.into_err_log(app);
+  }
+
+  #[cfg(feature = "tracing")]
+  info!("books hashed in {:?}", start.elapsed());
+
+  Ok(())
+}
+
+/// Date the books that were saved before the date they were added was tracked.
+/// The best guess is when their file was last modified, rather than when this runs,
+/// as that would put the whole library in filters such as "added this week".
+pub async fn date_books(app: &AppHandle) -> Result<()> {
+  let database = app.database_handle();
+  for model in database.get_books_without_added_at().await? {
+    // Books that can't be found are dated once they can.
+    let Ok(modified) = fs::metadata(&model.path)
+      .await
+      .and_then(|it| it.modified())
+    else {
+      continue;
+    };
+
+    let added_at = DateTime::<Utc>::from(modified)
+      .format(ADDED_AT_FORMAT)
+      .to_string();
+
+    database
+      .update_book_added_at(model.id, &added_at)
+      .await
+      .into_err_log(app);
+  }
+
+  Ok(())
+}
+
+/// Books whose title, series or writer match the query, from the most relevant.
+pub async fn search(
+  app: &AppHandle,
+  query: &str,
+  limit: Option<usize>,
+) -> Result<Vec<LibraryBook>> {
+  app
+    .database_handle()
+    .search_books(query, limit)
+    .await?
+    .iter()
+    .map(|model| LibraryBook::from_model(app, model))
+    .try_collect()
+}
+
+/// Groups of books with the same content, wherever they are.
+pub async fn get_duplicates(app: &AppHandle) -> Result<Vec<Vec<LibraryBook>>> {
+  app
+    .database_handle()
+    .get_duplicate_books()
+    .await?
+    .iter()
+    .map(|group| {
+      group
+        .iter()
+        .map(|model| LibraryBook::from_model(app, model))
+        .try_collect()
+    })
+    .try_collect()
+}
+
+/// Every book in the library.
+///
+/// The files of the books are never checked, so this stays fast even for large libraries,
+/// and every book is reported as present. Use [`check_files`] to find out which ones are
+/// missing, as it emits `BookMissing` for each of them.
+pub async fn get_all(app: &AppHandle) -> Result<Vec<LibraryBook>> {
+  app
+    .database_handle()
+    .get_all_books()
+    .await?
+    .iter()
+    .map(|model| LibraryBook::from_model(app, model))
+    .try_collect()
+}
+
+/// A page of the library, sorted and filtered as the query asks.
+pub async fn query(app: &AppHandle, query: LibraryQuery) -> Result<LibraryPage> {
+  let page = app.database_handle().query_books(query).await?;
+  let books = page
+    .books
+    .iter()
+    .map(|model| LibraryBook::from_model(app, model))
+    .try_collect()?;
+
+  Ok(LibraryPage { books, next: page.next })
+}
+
+/// Check whether the file of each book can still be found, flagging those that can't.
+/// Books that are there, but don't have a cover yet, have it extracted.
+pub async fn check_files(app: &AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  let start = Instant::now();
+
+  let semaphore = Arc::new(Semaphore::new(MAX_FILE_PERMITS));
+  let mut set = app
+    .database_handle()
+    .get_all_books()
+    .await?
+    .into_join_set_by(|model| {
+      let app = app.clone();
+      let semaphore = Arc::clone(&semaphore);
+      async move {
+        let _permit = semaphore.acquire_owned().await?;
+
+        // The file may be on a drive that isn't connected right now,
+        // so the book is only flagged as missing. Removing it is up to the user.
+        if let Ok(false) = fs::try_exists(&model.path).await {
+          Event::BookMissing(model.id).emit(&app)?;
+          return Ok(None);
+        }
+
+        let cover = app.path().cover(model.id)?;
+        let has_cover = fs::try_exists(&cover).await?;
+        Ok::<_, Error>((!has_cover).then_some(model))
+      }
+    });
+
+  let mut pending = Vec::new();
+  while let Some(result) = set.join_next().await {
+    match result? {
+      Ok(Some(model)) => pending.push(model),
+      Ok(None) => {}
+      Err(err) => Err::<(), _>(err).into_err_log(app),
+    }
+  }
+
+  if !pending.is_empty() {
+    schedule_cover_extraction(app, pending);
+  }
+
+  #[cfg(feature = "tracing")]
+  info!("library files checked in {:?}", start.elapsed());
+
+  Ok(())
+}
+
+fn schedule_cover_extraction<I>(app: &AppHandle, models: I)
+where
+  I: IntoIterator<Item = Book>,
+{
+  let permits = MAX_FILE_PERMITS / 5;
+  let semaphore = Arc::new(Semaphore::new(permits));
+  for model in models {
+    let app = app.clone();
+    let semaphore = Arc::clone(&semaphore);
+    spawn(async move {
+      let result: Result<()> = try {
+        let _permit = semaphore.acquire_owned().await?;
+        ActiveBook::from_model(&app, &model)?
+          .extract_cover()
+          .await?;
+      };
+
+      result.into_err_log(&app);
+    });
+  }
+}
+
+pub async fn remove(app: &AppHandle, id: i32) -> Result<()> {
+  app.database_handle().remove_book(id).await?;
+  Event::BookRemoved(id).emit(app)?;
+
+  if let Ok(cover) = app.path().cover(id) {
+    if fs::try_exists(&cover).await? {
+      fs::remove_file(cover).await?;
+    }
+  }
+
+  Ok(())
+}
+
+pub async fn remove_with_dialog(app: &AppHandle, id: i32) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  let title = app.database_handle().get_book_title(id).await?;
+
+  app
+    .dialog()
+    .message(format!("{title} will be removed from the library."))
+    .title("Remove book")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Remove")
+    .cancel_button_label("Cancel")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if rx.await? {
+    remove(app, id).await?;
+  }
+
+  Ok(())
+}
+
+#[cfg(feature = "devtools")]
+pub async fn remove_all(app: &AppHandle) -> Result<()> {
+  let handle = app.database_handle();
+  handle.remove_all_books().await?;
+  handle.remove_all_folders().await?;
+
+  Event::LibraryCleared.emit(app)?;
+
+  let path = app.path().cover_dir()?;
+  if let Ok(true) = fs::try_exists(&path).await {
+    fs::remove_dir_all(path).await?;
+  }
+
+  Ok(())
+}
+
+pub async fn get_folders(app: &AppHandle) -> Result<Vec<Folder>> {
+  app.database_handle().get_library_folders().await
+}
+
+/// Remove the folder from the library, optionally along with its books.
+/// Books that are also inside some other library folder are kept regardless.
+pub async fn remove_folder(app: &AppHandle, folder_id: i32, remove_books: bool) -> Result<()> {
+  let database = app.database_handle();
+  let folder = database.remove_folder(folder_id).await?;
+  let path = PathBuf::from(&folder.path);
+  let remaining = database.get_all_folders().await?;
+
+  // Folders nested inside this one may lose their watch along with it.
+  let watcher = app.library_watcher();
+  watcher.unwatch(&path).into_err_log(app);
+  for folder in remaining
+    .iter()
+    .filter(|it| it.starts_with(&path))
+  {
+    watcher.watch(folder).into_err_log(app);
+  }
+
+  if remove_books {
+    for book in database.get_books_in_folder(&path).await? {
+      let book_path = Path::new(&book.path);
+      if !remaining
+        .iter()
+        .any(|it| book_path.starts_with(it))
+      {
+        remove(app, book.id).await?;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+pub async fn remove_folder_with_dialog(app: &AppHandle, folder_id: i32) -> Result<()> {
+  let folders = app
+    .database_handle()
+    .get_library_folders()
+    .await?;
+  let Some(folder) = folders.into_iter().find(|it| it.id == folder_id) else {
+    return Ok(());
+  };
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message(format!("{} will be removed from the library.", folder.path))
+    .title("Remove folder")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Remove")
+    .cancel_button_label("Cancel")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if !rx.await? {
+    return Ok(());
+  }
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message("Should the books in this folder be removed from the library as well?")
+    .title("Remove folder")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Remove books")
+    .cancel_button_label("Keep books")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  let remove_books = rx.await?;
+  remove_folder(app, folder_id, remove_books).await
+}
+
+/// Point the folder to where it now lives, such as after moving it to another drive.
+/// The books keep their id, and everything tied to it, such as the rating and the cover.
+pub async fn relink_folder(app: &AppHandle, folder_id: i32, path: &Path) -> Result<()> {
+  let database = app.database_handle();
+  let previous = database
+    .get_library_folders()
+    .await?
+    .into_iter()
+    .find(|it| it.id == folder_id);
+
+  let relinked = database.relink_folder(folder_id, path).await?;
+
+  for id in relinked.replaced {
+    Event::BookRemoved(id).emit(app)?;
+    if let Ok(cover) = app.path().cover(id)
+      && fs::try_exists(&cover).await?
+    {
+      fs::remove_file(cover).await?;
+    }
+  }
+
+  // Adding a book that is already in the library replaces it.
+  let mut pending = Vec::new();
+  for model in relinked.books {
+    let mut book = LibraryBook::from_model(app, &model)?;
+    book.missing = matches!(fs::try_exists(&model.path).await, Ok(false));
+    Event::BookAdded(&book).emit(app)?;
+
+    if book.cover.is_none() && !book.missing {
+      pending.push(model);
+    }
+  }
+
+  if !pending.is_empty() {
+    schedule_cover_extraction(app, pending);
+  }
+
+  let watcher = app.library_watcher();
+  if let Some(previous) = previous {
+    // The old path is most likely gone already, taking its watch with it.
+    let _ = watcher.unwatch(Path::new(&previous.path));
+  }
+
+  watcher.watch(path).into_err_log(app);
+
+  // There may be new books in the folder since it was last seen.
+  let mut books = Vec::new();
+  let exclude = relinked.folder.exclude_matcher()?;
+  walk_folder(&mut books, path, &[exclude]);
+
+  if !books.is_empty() {
+    save_many(app, books).await?;
+  }
+
+  Ok(())
+}
+
+pub async fn relink_folder_with_dialog(app: &AppHandle, folder_id: i32) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  app.dialog().file().pick_folder(move |response| {
+    let _ = tx.send(response);
+  });
+
+  if let Some(path) = rx.await? {
+    relink_folder(app, folder_id, &path).await?;
+  }
+
+  Ok(())
+}
+
+/// Set the patterns for what should be skipped when searching the folder for books.
+/// Books already in the library that are now excluded are only removed if the user agrees,
+/// as that would also lose their rating, progress and so on.
+pub async fn set_folder_exclude(
+  app: &AppHandle,
+  folder_id: i32,
+  exclude: Vec<String>,
+) -> Result<Folder> {
+  let database = app.database_handle();
+  let folder = database
+    .update_folder_exclude(folder_id, Exclude(exclude))
+    .await?;
+
+  let matcher = folder.exclude_matcher()?;
+  let excluded = database
+    .get_books_in_folder(&folder.path)
+    .await?
+    .into_iter()
+    .filter(|book| {
+      let path = Path::new(&book.path);
+      matcher.is_excluded(path, path.is_dir())
+    })
+    .collect_vec();
+
+  if !excluded.is_empty() && remove_excluded_dialog(app, &excluded).await? {
+    for book in excluded {
+      remove(app, book.id).await?;
+    }
+  }
+
+  Ok(folder)
+}
+
+async fn remove_excluded_dialog(app: &AppHandle, books: &[Book]) -> Result<bool> {
+  const MAX_TITLES: usize = 10;
+
+  let mut titles = books
+    .iter()
+    .take(MAX_TITLES)
+    .map(|book| book.title.clone())
+    .collect_vec();
+
+  if books.len() > MAX_TITLES {
+    titles.push(format!("and {} more", books.len() - MAX_TITLES));
+  }
+
+  let message = format!(
+    "{} books in the library are now excluded. Should they be removed from it?\n\n{}",
+    books.len(),
+    titles.join("\n")
+  );
+
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message(message)
+    .title("Exclude books")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Remove books")
+    .cancel_button_label("Keep books")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  rx.await.map_err(Into::into)
+}
+
+pub async fn scan_book_folders(app: &AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  let start = Instant::now();
+
+  let mut books = Vec::new();
+  let folders = app
+    .database_handle()
+    .get_library_folders()
+    .await?;
+  let exclude = exclude_matchers(&folders)?;
+  for folder in &folders {
+    walk_folder(&mut books, Path::new(&folder.path), &exclude);
+  }
+
+  if !books.is_empty() {
+    save_many(app, books).await?;
+  }
+
+  #[cfg(feature = "tracing")]
+  info!("book folders scanned in {:?}", start.elapsed());
+
+  Ok(())
+}
+
+/// Search recursively for books within the folder, skipping whatever is excluded.
+/// Besides archives, folders of images without any subfolder are also considered books.
+/// The folder itself never is, even if it has nothing but images.
+fn walk_folder(books: &mut Vec<PathBuf>, folder: &Path, exclude: &[ExcludeMatcher]) {
+  let globset = glob::book();
+  let entries = WalkDir::new(folder)
+    .into_iter()
+    .filter_entry(|entry| !is_excluded(exclude, entry.path(), entry.file_type().is_dir()));
+
+  for entry in entries.flatten() {
+    let is_root = entry.depth() == 0;
+    let is_dir = entry.file_type().is_dir();
+    let path = entry.into_path();
+    if is_dir {
+      if !is_root && is_image_folder(&path) {
+        books.push(path);
+      }
+    } else if globset.is_match(&path) {
+      books.push(path);
+    }
+  }
+}
+
+fn exclude_matchers(folders: &[Folder]) -> Result<Vec<ExcludeMatcher>> {
+  folders
+    .iter()
+    .map(Folder::exclude_matcher)
+    .try_collect()
+}
+
+fn is_excluded(exclude: &[ExcludeMatcher], path: &Path, is_dir: bool) -> bool {
+  exclude
+    .iter()
+    .any(|it| it.is_excluded(path, is_dir))
+}
+
+/// Whether the folder has images and nothing else that could be a book.
+/// Folders with archives in them, like those of a series with a `cover.jpg`, are not books.
+fn is_image_folder(path: &Path) -> bool {
+  let Ok(entries) = std::fs::read_dir(path) else {
+    return false;
+  };
+
+  let books = glob::book();
+  let pages = glob::book_page();
+  let mut has_page = false;
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if path.is_dir() || books.is_match(&path) {
+      return false;
+    } else if !has_page && pages.is_match(&path) {
+      has_page = true;
+    }
+  }
+
+  has_page
+}
+
+#[cfg(feature = "devtools")]
+pub async fn add_mock_books(
+  app: &AppHandle,
+  amount: u8,
+  size: usize,
+  orientation: crate::image::mock::Orientation,
+) -> Result<()> {
+  use crate::image::mock::create_book;
+  use tokio::task::JoinSet;
+
+  let mut set = JoinSet::new();
+  for _ in 0..amount {
+    let app = app.clone();
+    set.spawn_blocking(move || create_book(&app, size, orientation));
+  }
+
+  let mut books = Vec::with_capacity(amount.into());
+  while let Some(book) = set.join_next().await {
+    books.push(book??);
+  }
+
+  if !books.is_empty() {
+    let path = app.path().mocks_dir()?.try_string()?;
+    app
+      .database_handle()
+      .save_folders([NewFolder { path }])
+      .await?;
+
+    save_many(app, books).await?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::book::{LibraryBook, SeriesParser};
+use crate::database::model::{Book, Series};
+use crate::prelude::*;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct LibrarySeries {
+  pub id: i32,
+  pub name: String,
+  /// Cover of the first book in the series.
+  pub cover: Option<PathBuf>,
+  pub books: usize,
+  /// How many of the books have been read.
+  pub read: usize,
+  /// First book, in reading order, that hasn't been read yet.
+  pub next_unread: Option<i32>,
+}
+
+impl LibrarySeries {
+  fn from_models(app: &AppHandle, series: Series, books: &[Book]) -> Result<Self> {
+    let cover = books
+      .first()
+      .map(|book| LibraryBook::from_model(app, book))
+      .transpose()?
+      .and_then(|book| book.cover);
+
+    Ok(Self {
+      id: series.id,
+      name: series.name,
+      cover,
+      books: books.len(),
+      read: books.iter().filter(|book| book.read).count(),
+      next_unread: books
+        .iter()
+        .find(|book| !book.read)
+        .map(|book| book.id),
+    })
+  }
+}
+
+/// Put the book in the series its metadata or file name tells, if any.
+///
+/// The parser is taken as an argument so that it's built only once when saving many books.
+/// The series the book leaves may end up empty, so [`remove_empty`] should be called afterwards.
+pub async fn assign(app: &AppHandle, parser: &SeriesParser, model: &Book) -> Result<()> {
+  let database = app.database_handle();
+  match parser.parse_model(model) {
+    Some(info) => database
+      .assign_series(model.id, info)
+      .await
+      .map(drop),
+    None => database.unassign_series(model.id).await,
+  }
+}
+
+/// Remove the series left without any book.
+pub async fn remove_empty(app: &AppHandle) -> Result<()> {
+  app.database_handle().remove_empty_series().await
+}
+
+/// Find the series of the books that don't have one yet.
+pub async fn assign_missing(app: &AppHandle) -> Result<()> {
+  #[cfg(feature = "tracing")]
+  let start = Instant::now();
+
+  let parser = SeriesParser::from_config(app)?;
+  let models = app
+    .database_handle()
+    .get_books_without_series()
+    .await?;
+
+  for model in &models {
+    assign(app, &parser, model)
+      .await
+      .into_err_log(app);
+  }
+
+  #[cfg(feature = "tracing")]
+  info!(
+    "series of {} books parsed in {:?}",
+    models.len(),
+    start.elapsed()
+  );
+
+  Ok(())
+}
+
+/// Save the patterns, then parse the series of every book again in the background.
+pub fn set_patterns(app: &AppHandle, patterns: Vec<String>) -> Result<()> {
+  SeriesParser::set_patterns(app, patterns)?;
+
+  let app = app.clone();
+  spawn(async move {
+    let result: Result<()> = try {
+      app.database_handle().clear_series().await?;
+      assign_missing(&app).await?;
+    };
+
+    result.into_err_log(&app);
+  });
+
+  Ok(())
+}
+
+pub async fn get_all(app: &AppHandle) -> Result<Vec<LibrarySeries>> {
+  app
+    .database_handle()
+    .get_all_series()
+    .await?
+    .into_iter()
+    .map(|(series, books)| LibrarySeries::from_models(app, series, &books))
+    .try_collect()
+}
+
+/// Books of the series, in reading order.
+pub async fn get_books(app: &AppHandle, series_id: i32) -> Result<Vec<LibraryBook>> {
+  app
+    .database_handle()
+    .get_series_books(series_id)
+    .await?
+    .iter()
+    .map(|model| LibraryBook::from_model(app, model))
+    .try_collect()
+}
+
+pub async fn get_next(app: &AppHandle, book_id: i32) -> Result<Option<LibraryBook>> {
+  app
+    .database_handle()
+    .get_next_in_series(book_id)
+    .await?
+    .map(|model| LibraryBook::from_model(app, &model))
+    .transpose()
+}
+
+pub async fn get_previous(app: &AppHandle, book_id: i32) -> Result<Option<LibraryBook>> {
+  app
+    .database_handle()
+    .get_previous_in_series(book_id)
+    .await?
+    .map(|model| LibraryBook::from_model(app, &model))
+    .transpose()
+}
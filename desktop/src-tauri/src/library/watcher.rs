@@ -0,0 +1,302 @@
+use super::{
+  exclude_matchers, is_excluded, is_image_folder, remove, save_many, series, walk_folder,
+};
+use crate::book::{LibraryBook, SeriesParser};
+use crate::event::Event;
+use crate::prelude::*;
+use crate::utils::glob;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::time::timeout;
+
+/// How long the folders must stay quiet before the changes are applied.
+/// Copying a large archive, for instance, produces a burst of events.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Watches the library folders, keeping the library in sync with them.
+#[derive(Clone)]
+pub struct LibraryWatcher {
+  watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+impl LibraryWatcher {
+  pub fn new(app: &AppHandle) -> Result<Self> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |result| {
+      let _ = tx.send(result);
+    })?;
+
+    let app = app.clone();
+    spawn(async move { debounce(&app, rx).await });
+
+    Ok(Self {
+      watcher: Arc::new(Mutex::new(watcher)),
+    })
+  }
+
+  pub fn watch(&self, folder: &Path) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    debug!(watch_folder = %folder.display());
+
+    self
+      .watcher
+      .lock()
+      .unwrap()
+      .watch(folder, RecursiveMode::Recursive)
+      .map_err(Into::into)
+  }
+
+  pub fn unwatch(&self, folder: &Path) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    debug!(unwatch_folder = %folder.display());
+
+    self
+      .watcher
+      .lock()
+      .unwrap()
+      .unwatch(folder)
+      .map_err(Into::into)
+  }
+}
+
+/// Start watching every folder in the library.
+pub async fn watch_folders(app: &AppHandle) -> Result<()> {
+  let watcher = app.library_watcher();
+  for folder in app.database_handle().get_all_folders().await? {
+    // The folder may be on a drive that isn't connected right now.
+    watcher.watch(&folder).into_err_log(app);
+  }
+
+  Ok(())
+}
+
+async fn debounce(app: &AppHandle, mut rx: UnboundedReceiver<notify::Result<notify::Event>>) {
+  let mut changes = Changes::default();
+  loop {
+    let received = if changes.is_empty() {
+      rx.recv().await
+    } else {
+      match timeout(DEBOUNCE, rx.recv()).await {
+        Ok(received) => received,
+        Err(_) => {
+          let changes = mem::take(&mut changes);
+          changes.apply(app).await.into_err_log(app);
+          continue;
+        }
+      }
+    };
+
+    match received {
+      Some(Ok(event)) => changes.push(event),
+      Some(Err(err)) => Err::<(), Error>(err.into()).into_err_log(app),
+      None => break,
+    }
+  }
+}
+
+#[derive(Default)]
+struct Changes {
+  created: Vec<PathBuf>,
+  removed: Vec<PathBuf>,
+  renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Changes {
+  fn is_empty(&self) -> bool {
+    self.created.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+  }
+
+  fn push(&mut self, event: notify::Event) {
+    #[cfg(feature = "tracing")]
+    trace!(?event);
+
+    let mut paths = event.paths;
+    match event.kind {
+      EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+        if let [from, to] = paths.as_slice() {
+          self.renamed.push((from.clone(), to.clone()));
+        }
+      }
+      EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+        self.created.append(&mut paths);
+      }
+      EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+        self.removed.append(&mut paths);
+      }
+      // Some platforms can't tell which side of the rename the path is on.
+      EventKind::Modify(ModifyKind::Name(_)) => {
+        for path in paths {
+          if path.exists() {
+            self.created.push(path);
+          } else {
+            self.removed.push(path);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  /// Bring the library up to date with the changes.
+  ///
+  /// A path may have come and gone within the same batch, so only
+  /// created paths that still exist and removed paths that are still missing are considered.
+  async fn apply(self, app: &AppHandle) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    let start = Instant::now();
+
+    let mut created = self
+      .created
+      .into_iter()
+      .unique()
+      .filter(|it| it.exists())
+      .collect_vec();
+
+    let removed = self
+      .removed
+      .into_iter()
+      .unique()
+      .filter(|it| !it.exists())
+      .collect_vec();
+
+    for (from, to) in self.renamed {
+      if !move_books(app, &from, &to).await? {
+        created.push(to);
+      }
+    }
+
+    // Moving between folders may be reported as a removal followed by a creation.
+    // In that case, the best we can do is to match them by name.
+    for from in removed {
+      let to = created
+        .iter()
+        .position(|it| it.file_name() == from.file_name())
+        .map(|index| created.swap_remove(index));
+
+      if let Some(to) = to {
+        if !move_books(app, &from, &to).await? {
+          created.push(to);
+        }
+      } else {
+        // Books are only ever removed when the user asks for it.
+        // The file may well come back, as when the drive it's on is connected again.
+        let database = app.database_handle();
+        for book in database.get_books_in_folder(&from).await? {
+          Event::BookMissing(book.id).emit(app)?;
+        }
+      }
+    }
+
+    for path in &created {
+      found_books(app, path).await?;
+    }
+
+    let folders = app
+      .database_handle()
+      .get_library_folders()
+      .await?;
+    let exclude = exclude_matchers(&folders)?;
+
+    // The library folders themselves are never books, even if they have nothing but images.
+    let is_root = |path: &Path| {
+      folders
+        .iter()
+        .any(|it| Path::new(&it.path) == path)
+    };
+
+    let globset = glob::book();
+    let mut books = Vec::new();
+    for path in created {
+      if is_excluded(&exclude, &path, path.is_dir()) {
+        continue;
+      } else if path.is_dir() {
+        // Walking the folder would never consider it a book on its own.
+        if !is_root(&path) && is_image_folder(&path) {
+          books.push(path);
+        } else {
+          walk_folder(&mut books, &path, &exclude);
+        }
+      } else if globset.is_match(&path) {
+        books.push(path);
+      } else if let Some(parent) = path.parent()
+        && !is_root(parent)
+        && !is_excluded(&exclude, parent, true)
+        && is_image_folder(parent)
+      {
+        books.push(parent.to_owned());
+      }
+    }
+
+    // Books already in the library are skipped.
+    let books = books.into_iter().unique().collect_vec();
+    if !books.is_empty() {
+      save_many(app, books).await?;
+    }
+
+    #[cfg(feature = "tracing")]
+    info!("library folder changes applied in {:?}", start.elapsed());
+
+    Ok(())
+  }
+}
+
+/// Let the library know that the books at or below the path, if any, can be found again.
+async fn found_books(app: &AppHandle, path: &Path) -> Result<()> {
+  for model in app
+    .database_handle()
+    .get_books_in_folder(path)
+    .await?
+  {
+    // Adding a book that is already in the library replaces it.
+    let book = LibraryBook::from_model(app, &model)?;
+    Event::BookAdded(&book).emit(app)?;
+  }
+
+  Ok(())
+}
+
+/// Point every book at or below `from` to its new place under `to`.
+/// Their id, and everything tied to it, such as the rating and the cover, are kept.
+///
+/// Returns whether any book was moved.
+async fn move_books(app: &AppHandle, from: &Path, to: &Path) -> Result<bool> {
+  let database = app.database_handle();
+  let books = database.get_books_in_folder(from).await?;
+  if books.is_empty() {
+    return Ok(false);
+  }
+
+  let parser = SeriesParser::from_config(app)?;
+  for book in books {
+    let Ok(path) = book.path.try_rebase(from, to) else {
+      continue;
+    };
+
+    // The book may have been moved over another one.
+    if let Ok(existing) = database.get_book_by_path(&path).await
+      && existing.id != book.id
+    {
+      remove(app, existing.id).await?;
+    }
+
+    #[cfg(feature = "tracing")]
+    debug!(move_book = book.id, from = %book.path, to = %path.display());
+
+    let model = database.update_book_path(book.id, &path).await?;
+    series::assign(app, &parser, &model)
+      .await
+      .into_err_log(app);
+
+    let book = LibraryBook::from_model(app, &model)?;
+    Event::BookRemoved(book.id).emit(app)?;
+    Event::BookAdded(&book).emit(app)?;
+  }
+
+  series::remove_empty(app).await.into_err_log(app);
+
+  Ok(true)
+}
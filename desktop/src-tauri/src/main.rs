@@ -19,12 +19,14 @@ mod prelude;
 mod reader;
 mod result;
 mod server;
+mod trash;
 mod utils;
 mod window;
 
-use manager::Kotori;
+use manager::{Kotori, ManagerExt};
 use result::{BoxResult, Result, ResultExt};
-use tauri::App;
+use tauri::async_runtime::block_on;
+use tauri::{App, AppHandle, Manager, RunEvent};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -44,25 +46,77 @@ fn main() {
     .invoke_handler(tauri::generate_handler![
       command::close_window,
       command::focus_main_window,
+      command::get_book_cache_stats,
       command::server_port,
       command::show_window,
       command::toggle_fullscreen,
+      command::collection::add_to_collection,
+      command::collection::create_collection,
+      command::collection::create_smart_collection,
+      command::collection::get_collection_books,
       command::collection::get_collections,
+      command::collection::remove_collection,
+      command::collection::remove_from_collection,
+      command::collection::rename_collection,
+      command::collection::update_smart_collection,
+      command::database::backup_database,
+      command::database::get_database_backups,
+      command::database::restore_database_backup,
+      command::database::restore_database_backup_with_dialog,
+      command::folder::get_library_folders,
+      command::folder::relink_library_folder,
+      command::folder::relink_library_folder_with_dialog,
+      command::folder::remove_library_folder,
+      command::folder::remove_library_folder_with_dialog,
+      command::folder::set_folder_exclude,
       command::library::add_to_library_with_dialog,
+      command::library::check_library_files,
+      command::library::export_library,
+      command::library::export_library_with_dialog,
+      command::library::get_duplicate_books,
       command::library::get_library_books,
+      command::library::get_reading_history,
+      command::library::get_recently_read,
+      command::library::import_library,
+      command::library::import_library_with_dialog,
+      command::library::query_library,
       command::library::remove_book,
       command::library::remove_book_with_dialog,
+      command::library::search_books,
       command::library::show_library_book_context_menu,
       command::library::update_book_rating,
       command::reader::delete_page_with_dialog,
       command::reader::get_current_reader_book,
       command::reader::open_book,
       command::reader::open_book_with_dialog,
+      command::reader::open_next_book,
+      command::reader::open_previous_book,
       command::reader::show_reader_page_context_menu,
       command::reader::switch_reader_focus,
+      command::reader::undo_delete_page,
+      command::reader::update_reader_progress,
+      command::series::get_all_series,
+      command::series::get_next_in_series,
+      command::series::get_previous_in_series,
+      command::series::get_series_books,
+      command::series::get_series_patterns,
+      command::series::set_series_patterns,
+      command::tag::add_tag_to_book,
+      command::tag::create_tag,
+      command::tag::get_book_tags,
+      command::tag::get_tags,
+      command::tag::remove_tag,
+      command::tag::remove_tag_from_book,
+      command::tag::rename_tag,
+      command::tag::set_book_tags,
+      command::trash::empty_trash_with_dialog,
+      command::trash::get_trashed_pages,
+      command::trash::purge_trashed_page,
+      command::trash::restore_trashed_page,
     ])
-    .run(tauri::generate_context!())
-    .expect("could not start kotori");
+    .build(tauri::generate_context!())
+    .expect("could not start kotori")
+    .run(on_event);
 }
 
 fn setup(app: &mut App) -> BoxResult<()> {
@@ -84,6 +138,16 @@ fn setup(app: &mut App) -> BoxResult<()> {
   Ok(())
 }
 
+fn on_event(app: &AppHandle, event: RunEvent) {
+  // Metadata waiting to be written would otherwise be lost, whatever the reason for exiting.
+  // Kotori may not even be initialized if the setup failed.
+  if let RunEvent::ExitRequested { .. } = event
+    && app.try_state::<Kotori>().is_some()
+  {
+    block_on(app.book_handle().flush());
+  }
+}
+
 mod plugin {
   use crate::manager::ManagerExt;
   use crate::result::ResultExt;
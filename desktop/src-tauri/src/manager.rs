@@ -1,13 +1,16 @@
 use crate::book::BookHandle;
 use crate::database::DatabaseHandle;
+use crate::library::{self, LibraryWatcher};
 use crate::reader::{Reader, WindowMap};
-use crate::result::Result;
+use crate::result::{Result, ResultExt};
 use crate::window::WindowKind;
+use tauri::async_runtime::spawn;
 use tauri::{AppHandle, Manager, State, WebviewWindow, Window, Wry};
 
 pub struct Kotori {
   database_handle: DatabaseHandle,
   book_handle: BookHandle,
+  library_watcher: LibraryWatcher,
   reader: Reader,
 }
 
@@ -15,12 +18,28 @@ impl Kotori {
   pub fn init(app: &AppHandle) -> Result<()> {
     let kotori = Self {
       database_handle: DatabaseHandle::new(app)?,
-      book_handle: BookHandle::new(),
+      book_handle: BookHandle::new(app)?,
+      library_watcher: LibraryWatcher::new(app)?,
       reader: Reader::new(),
     };
 
     app.manage(kotori);
 
+    let app = app.clone();
+    spawn(async move {
+      library::watch_folders(&app)
+        .await
+        .into_err_log(&app);
+
+      library::hash_books(&app).await.into_err_log(&app);
+      library::date_books(&app).await.into_err_log(&app);
+      library::series::assign_missing(&app)
+        .await
+        .into_err_log(&app);
+
+      library::expire_smart_collections(&app).await;
+    });
+
     Ok(())
   }
 }
@@ -38,6 +57,10 @@ pub trait ManagerExt: Manager<Wry> {
     self.kotori().database_handle.clone()
   }
 
+  fn library_watcher(&self) -> LibraryWatcher {
+    self.kotori().library_watcher.clone()
+  }
+
   fn main_window(&self) -> WebviewWindow {
     let label = WindowKind::Main.label();
     self
@@ -1,4 +1,4 @@
-use crate::book::ActiveBook;
+use crate::book::{ActiveBook, BookHandle, ComicInfo, MetadataStorage};
 #[cfg(feature = "devtools")]
 use crate::image::mock::Orientation;
 use crate::menu::prelude::*;
@@ -23,6 +23,18 @@ pub enum Item {
   ColorModeLight,
   #[strum(serialize = "kt-app-discord")]
   Discord,
+  #[strum(serialize = "kt-app-export-library")]
+  ExportLibrary,
+  #[strum(serialize = "kt-app-import-library")]
+  ImportLibrary,
+  #[strum(serialize = "kt-app-keep-book-backup")]
+  KeepBookBackup,
+  #[strum(serialize = "kt-app-metadata-storage-app-data")]
+  MetadataStorageAppData,
+  #[strum(serialize = "kt-app-metadata-storage-archive")]
+  MetadataStorageArchive,
+  #[strum(serialize = "kt-app-metadata-storage-next-to-book")]
+  MetadataStorageNextToBook,
   #[strum(serialize = "kt-app-random-book")]
   RandomBook,
   #[strum(serialize = "kt-app-repository")]
@@ -31,6 +43,8 @@ pub enum Item {
   ScanBookFolders,
   #[strum(serialize = "kt-app-open-file")]
   OpenFile,
+  #[strum(serialize = "kt-app-write-comic-info")]
+  WriteComicInfo,
 
   #[cfg(feature = "devtools")]
   #[strum(serialize = "kt-app-add-mock-books-landscape")]
@@ -49,8 +63,63 @@ impl Item {
   }
 }
 
+/// Books in the "Continue reading" submenu can't be part of the `Item` enum.
+/// Instead, their ids are built from this prefix and the id of the book.
+const CONTINUE_READING_ITEM_PREFIX: &str = "kt-app-continue-reading-";
+const CONTINUE_READING_MENU_ID: &str = "kt-app-continue-reading";
+const CONTINUE_READING_LIMIT: usize = 10;
+const READ_MENU_ID: &str = "kt-app-read";
+
+/// Same as the "Continue reading" submenu, but for the library folders.
+const RELINK_FOLDER_ITEM_PREFIX: &str = "kt-app-relink-folder-";
+const RELINK_FOLDER_MENU_ID: &str = "kt-app-relink-folder";
+const REMOVE_FOLDER_ITEM_PREFIX: &str = "kt-app-remove-folder-";
+const REMOVE_FOLDER_MENU_ID: &str = "kt-app-remove-folder";
+const FILE_MENU_ID: &str = "kt-app-file";
+
+fn dynamic_menu_id(prefix: &str, id: i32) -> MenuId {
+  MenuId::new(format!("{prefix}{id}"))
+}
+
+fn id_from_event(event: &MenuEvent, prefix: &str) -> Option<i32> {
+  event
+    .id()
+    .as_ref()
+    .strip_prefix(prefix)?
+    .parse()
+    .ok()
+}
+
+/// Find a submenu nested inside one of the top-level submenus.
+fn find_submenu(menu: &Menu<Wry>, parent_id: &str, id: &str) -> Option<Submenu<Wry>> {
+  menu
+    .get(parent_id)?
+    .as_submenu()?
+    .get(id)?
+    .as_submenu()
+    .cloned()
+}
+
 impl Listener for Item {
   fn execute(window: &Window, event: &MenuEvent) {
+    if let Some(book_id) = id_from_event(event, CONTINUE_READING_ITEM_PREFIX) {
+      let app = window.app_handle().clone();
+      spawn(async move { continue_reading(&app, book_id).await });
+      return;
+    }
+
+    if let Some(folder_id) = id_from_event(event, RELINK_FOLDER_ITEM_PREFIX) {
+      let app = window.app_handle().clone();
+      spawn(async move { relink_folder(&app, folder_id).await });
+      return;
+    }
+
+    if let Some(folder_id) = id_from_event(event, REMOVE_FOLDER_ITEM_PREFIX) {
+      let app = window.app_handle().clone();
+      spawn(async move { remove_folder(&app, folder_id).await });
+      return;
+    }
+
     let item = menu_item_or_bail!(event);
     let app = window.app_handle().clone();
     spawn(async move {
@@ -61,10 +130,17 @@ impl Listener for Item {
         Item::ColorModeDark => set_color_mode(&app, ColorMode::Dark).await,
         Item::ColorModeLight => set_color_mode(&app, ColorMode::Light).await,
         Item::Discord => open_discord(&app),
+        Item::ExportLibrary => export_library(&app).await,
+        Item::ImportLibrary => import_library(&app).await,
+        Item::KeepBookBackup => toggle_keep_book_backup(&app),
+        Item::MetadataStorageAppData => set_metadata_storage(&app, MetadataStorage::AppData),
+        Item::MetadataStorageArchive => set_metadata_storage(&app, MetadataStorage::Archive),
+        Item::MetadataStorageNextToBook => set_metadata_storage(&app, MetadataStorage::NextToBook),
         Item::OpenFile => open_file(&app).await,
         Item::RandomBook => open_random_book(&app).await,
         Item::Repository => open_repository(&app),
         Item::ScanBookFolders => scan_book_folders(&app).await,
+        Item::WriteComicInfo => toggle_write_comic_info(&app),
 
         #[cfg(feature = "devtools")]
         Item::AddMockBooksLandscape => add_mock_books(&app, Orientation::Landscape).await,
@@ -101,6 +177,21 @@ impl AppMenu {
 
       let has_folders = app.database_handle().has_any_folder().await?;
       menu.set_item_enabled(&Item::ScanBookFolders.to_menu_id(), !has_folders)?;
+
+      if let Some(submenu) = find_submenu(&menu, FILE_MENU_ID, RELINK_FOLDER_MENU_ID) {
+        while submenu.remove_at(0)?.is_some() {}
+        FileMenu::append_folders(app, &submenu, RELINK_FOLDER_ITEM_PREFIX).await?;
+      }
+
+      if let Some(submenu) = find_submenu(&menu, FILE_MENU_ID, REMOVE_FOLDER_MENU_ID) {
+        while submenu.remove_at(0)?.is_some() {}
+        FileMenu::append_folders(app, &submenu, REMOVE_FOLDER_ITEM_PREFIX).await?;
+      }
+
+      if let Some(submenu) = find_submenu(&menu, READ_MENU_ID, CONTINUE_READING_MENU_ID) {
+        while submenu.remove_at(0)?.is_some() {}
+        ReadMenu::append_recent_books(app, &submenu).await?;
+      }
     }
 
     Ok(())
@@ -119,13 +210,32 @@ struct FileMenu(Submenu<Wry>);
 
 impl FileMenu {
   fn new<M: Manager<Wry>>(app: &M) -> Result<Self> {
-    let mut menu = SubmenuBuilder::new(app, "File")
+    // Like the "Continue reading" submenu, the folders are added on the first update.
+    let relink_folder =
+      SubmenuBuilder::with_id(app, RELINK_FOLDER_MENU_ID, "Relink folder").build()?;
+    let remove_folder =
+      SubmenuBuilder::with_id(app, REMOVE_FOLDER_MENU_ID, "Remove folder").build()?;
+
+    let mut menu = SubmenuBuilder::with_id(app, FILE_MENU_ID, "File")
       .items(&[
         &mi!(app, OpenFile, "Open file")?,
         &mi!(app, AddToLibrary, "Add to library")?,
+        &relink_folder,
+        &remove_folder,
       ])
       .separator()
-      .items(&[&mi!(app, ScanBookFolders, "Scan book folders")?]);
+      .items(&[&mi!(app, ScanBookFolders, "Scan book folders")?])
+      .separator()
+      .items(&[
+        &mi!(app, ExportLibrary, "Export library")?,
+        &mi!(app, ImportLibrary, "Import library")?,
+      ])
+      .separator()
+      .items(&[
+        &FileMenu::metadata_storage(app)?,
+        &FileMenu::write_comic_info(app)?,
+        &FileMenu::keep_book_backup(app)?,
+      ]);
 
     if !cfg!(target_os = "linux") {
       menu = menu.separator().quit();
@@ -133,18 +243,102 @@ impl FileMenu {
 
     menu.build().map(Self).map_err(Into::into)
   }
+
+  async fn append_folders(app: &AppHandle, submenu: &Submenu<Wry>, prefix: &str) -> Result<()> {
+    let folders = app
+      .database_handle()
+      .get_library_folders()
+      .await?;
+
+    if folders.is_empty() {
+      let item = MenuItemBuilder::new("No folders")
+        .enabled(false)
+        .build(app)?;
+
+      return submenu.append(&item).map_err(Into::into);
+    }
+
+    for folder in folders {
+      let id = dynamic_menu_id(prefix, folder.id);
+      let item = MenuItemBuilder::with_id(id, &folder.path).build(app)?;
+      submenu.append(&item)?;
+    }
+
+    Ok(())
+  }
+
+  fn keep_book_backup<M: Manager<Wry>>(app: &M) -> Result<CheckMenuItem<Wry>> {
+    let enabled = BookHandle::is_backup_enabled(app.app_handle())?;
+    CheckMenuItemBuilder::with_id(Item::KeepBookBackup, "Keep backup of rewritten books")
+      .checked(enabled)
+      .build(app)
+      .map_err(Into::into)
+  }
+
+  fn metadata_storage<M: Manager<Wry>>(app: &M) -> Result<Submenu<Wry>> {
+    let storage = MetadataStorage::get(app.app_handle())?;
+    SubmenuBuilder::new(app, "Store metadata")
+      .items(&[
+        &CheckMenuItemBuilder::with_id(Item::MetadataStorageArchive, "Inside the book")
+          .checked(storage == MetadataStorage::Archive)
+          .build(app)?,
+        &CheckMenuItemBuilder::with_id(Item::MetadataStorageNextToBook, "Next to the book")
+          .checked(storage == MetadataStorage::NextToBook)
+          .build(app)?,
+        &CheckMenuItemBuilder::with_id(Item::MetadataStorageAppData, "In the app data")
+          .checked(storage == MetadataStorage::AppData)
+          .build(app)?,
+      ])
+      .build()
+      .map_err(Into::into)
+  }
+
+  fn write_comic_info<M: Manager<Wry>>(app: &M) -> Result<CheckMenuItem<Wry>> {
+    let enabled = ComicInfo::is_write_enabled(app.app_handle())?;
+    CheckMenuItemBuilder::with_id(Item::WriteComicInfo, "Write ComicInfo.xml")
+      .checked(enabled)
+      .build(app)
+      .map_err(Into::into)
+  }
 }
 
 struct ReadMenu(Submenu<Wry>);
 
 impl ReadMenu {
   fn new<M: Manager<Wry>>(app: &M) -> Result<Self> {
-    SubmenuBuilder::new(app, "Read")
-      .items(&[&mi!(app, RandomBook, "Random book")?])
+    // The history can only be read asynchronously, so the books are added on the first update.
+    let continue_reading =
+      SubmenuBuilder::with_id(app, CONTINUE_READING_MENU_ID, "Continue reading").build()?;
+
+    SubmenuBuilder::with_id(app, READ_MENU_ID, "Read")
+      .items(&[&continue_reading, &mi!(app, RandomBook, "Random book")?])
       .build()
       .map(Self)
       .map_err(Into::into)
   }
+
+  async fn append_recent_books(app: &AppHandle, submenu: &Submenu<Wry>) -> Result<()> {
+    let books = app
+      .database_handle()
+      .get_recently_read(CONTINUE_READING_LIMIT)
+      .await?;
+
+    if books.is_empty() {
+      let item = MenuItemBuilder::new("Nothing read yet")
+        .enabled(false)
+        .build(app)?;
+
+      return submenu.append(&item).map_err(Into::into);
+    }
+
+    for book in books {
+      let id = dynamic_menu_id(CONTINUE_READING_ITEM_PREFIX, book.id);
+      let item = MenuItemBuilder::with_id(id, &book.title).build(app)?;
+      submenu.append(&item)?;
+    }
+
+    Ok(())
+  }
 }
 
 struct ViewMenu(Submenu<Wry>);
@@ -252,6 +446,41 @@ async fn add_to_library(app: &AppHandle) {
     .into_err_dialog(app);
 }
 
+async fn continue_reading(app: &AppHandle, book_id: i32) {
+  let result: Result<()> = try {
+    let book = ActiveBook::from_id(app, book_id).await?;
+    reader::open_book(app, book).await?;
+  };
+
+  result.into_err_dialog(app);
+}
+
+async fn export_library(app: &AppHandle) {
+  library::bundle::export_with_dialog(app)
+    .await
+    .into_err_dialog(app);
+}
+
+/// Paths can't be rewritten from here, so books in other places will only be found by their hash.
+/// Those not already in the library are left out, rather than added with a path that doesn't exist.
+async fn import_library(app: &AppHandle) {
+  library::bundle::import_with_dialog(app, Vec::new())
+    .await
+    .into_err_dialog(app);
+}
+
+async fn relink_folder(app: &AppHandle, folder_id: i32) {
+  library::relink_folder_with_dialog(app, folder_id)
+    .await
+    .into_err_dialog(app);
+}
+
+async fn remove_folder(app: &AppHandle, folder_id: i32) {
+  library::remove_folder_with_dialog(app, folder_id)
+    .await
+    .into_err_dialog(app);
+}
+
 async fn open_file(app: &AppHandle) {
   crate::book::open_with_dialog(app)
     .await
@@ -318,6 +547,54 @@ async fn scan_book_folders(app: &AppHandle) {
     .into_err_dialog(app);
 }
 
+/// Books are left untouched while their metadata is stored in a sidecar,
+/// so pages can't be deleted and `ComicInfo.xml` isn't written.
+fn set_metadata_storage(app: &AppHandle, storage: MetadataStorage) {
+  let result: Result<()> = try {
+    storage.set(app)?;
+
+    if let Some(menu) = app.main_window().menu() {
+      let items = [
+        (Item::MetadataStorageAppData, MetadataStorage::AppData),
+        (Item::MetadataStorageArchive, MetadataStorage::Archive),
+        (Item::MetadataStorageNextToBook, MetadataStorage::NextToBook),
+      ];
+
+      for (item, value) in items {
+        menu.set_item_checked(&item.to_menu_id(), value == storage)?;
+      }
+    }
+  };
+
+  result.into_err_dialog(app);
+}
+
+fn toggle_keep_book_backup(app: &AppHandle) {
+  let result: Result<()> = try {
+    let enabled = !BookHandle::is_backup_enabled(app)?;
+    BookHandle::set_backup_enabled(app, enabled)?;
+
+    if let Some(menu) = app.main_window().menu() {
+      menu.set_item_checked(&Item::KeepBookBackup.to_menu_id(), enabled)?;
+    }
+  };
+
+  result.into_err_dialog(app);
+}
+
+fn toggle_write_comic_info(app: &AppHandle) {
+  let result: Result<()> = try {
+    let enabled = !ComicInfo::is_write_enabled(app)?;
+    ComicInfo::set_write_enabled(app, enabled)?;
+
+    if let Some(menu) = app.main_window().menu() {
+      menu.set_item_checked(&Item::WriteComicInfo.to_menu_id(), enabled)?;
+    }
+  };
+
+  result.into_err_dialog(app);
+}
+
 async fn set_color_mode(app: &AppHandle, mode: ColorMode) {
   use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
   use tauri_plugin_manatsu::AppHandleExt as _;
@@ -340,6 +617,9 @@ async fn set_color_mode(app: &AppHandle, mode: ColorMode) {
     let _ = app.save_window_state(StateFlags::all());
     let _ = app.write_logs_to_disk();
 
+    // Restarting doesn't give a chance to flush the metadata waiting to be written.
+    app.book_handle().flush().await;
+
     // Kotori will crash in dev mode after restarting.
     app.restart();
   }
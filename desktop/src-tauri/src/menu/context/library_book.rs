@@ -1,5 +1,5 @@
 use crate::book::ActiveBook;
-use crate::database::model::Book;
+use crate::database::model::{Book, Collection, Tag};
 use crate::manager::ManagerExt;
 use crate::menu::context::ContextMenuUpdate;
 use crate::menu::prelude::*;
@@ -28,8 +28,55 @@ impl Item {
   }
 }
 
+/// Collections are created by the user, so their items can't be part of the `Item` enum.
+/// Instead, their ids are built from this prefix and the id of the collection.
+const COLLECTION_ITEM_PREFIX: &str = "kt-ctx-library-book-collection-";
+const COLLECTION_MENU_ID: &str = "kt-ctx-library-book-collections";
+
+fn collection_menu_id(collection_id: i32) -> MenuId {
+  MenuId::new(format!("{COLLECTION_ITEM_PREFIX}{collection_id}"))
+}
+
+fn collection_id_from_event(event: &MenuEvent) -> Option<i32> {
+  event
+    .id()
+    .as_ref()
+    .strip_prefix(COLLECTION_ITEM_PREFIX)?
+    .parse()
+    .ok()
+}
+
+/// Same as collections, tags are built from this prefix and the id of the tag.
+const TAG_ITEM_PREFIX: &str = "kt-ctx-library-book-tag-";
+const TAG_MENU_ID: &str = "kt-ctx-library-book-tags";
+
+fn tag_menu_id(tag_id: i32) -> MenuId {
+  MenuId::new(format!("{TAG_ITEM_PREFIX}{tag_id}"))
+}
+
+fn tag_id_from_event(event: &MenuEvent) -> Option<i32> {
+  event
+    .id()
+    .as_ref()
+    .strip_prefix(TAG_ITEM_PREFIX)?
+    .parse()
+    .ok()
+}
+
 impl Listener for Item {
   fn execute(window: &Window, event: &MenuEvent) {
+    if let Some(collection_id) = collection_id_from_event(event) {
+      let app = window.app_handle().clone();
+      spawn(async move { toggle_collection(&app, collection_id).await });
+      return;
+    }
+
+    if let Some(tag_id) = tag_id_from_event(event) {
+      let app = window.app_handle().clone();
+      spawn(async move { toggle_tag(&app, tag_id).await });
+      return;
+    }
+
     let item = menu_item_or_bail!(event);
     let app = window.app_handle().clone();
     spawn(async move {
@@ -47,15 +94,41 @@ impl Listener for Item {
 #[derive(Clone, Debug)]
 pub struct Context {
   pub book: Book,
+  pub collections: Vec<Collection>,
+  /// Ids of the collections containing the book.
+  pub book_collections: Vec<i32>,
+  pub tags: Vec<Tag>,
+  /// Ids of the tags the book has.
+  pub book_tags: Vec<i32>,
 }
 
 impl Context {
   pub async fn new<M: ManagerExt>(manager: &M, id: i32) -> Result<Self> {
-    manager
-      .database_handle()
-      .get_book_by_id(id)
-      .await
-      .map(|book| Self { book })
+    let handle = manager.database_handle();
+    let book = handle.get_book_by_id(id).await?;
+    let collections = handle.get_all_collections().await?;
+    let book_collections = handle
+      .get_book_collections(id)
+      .await?
+      .into_iter()
+      .map(|it| it.id)
+      .collect();
+
+    let tags = handle.get_all_tags().await?;
+    let book_tags = handle
+      .get_book_tags(id)
+      .await?
+      .into_iter()
+      .map(|it| it.id)
+      .collect();
+
+    Ok(Self {
+      book,
+      collections,
+      book_collections,
+      tags,
+      book_tags,
+    })
   }
 }
 
@@ -72,7 +145,11 @@ impl LibraryBookContextMenu {
         &mi!(app, RemoveBook, "Remove")?,
       ])
       .separator()
-      .items(&[&*MarkAsMenu::new(app, &ctx)?])
+      .items(&[
+        &*MarkAsMenu::new(app, &ctx)?,
+        &*CollectionMenu::new(app, &ctx)?,
+        &*TagMenu::new(app, &ctx)?,
+      ])
       .separator()
       .items(&[&mi!(app, OpenBookFolder, "Open folder")?])
       .build()?;
@@ -101,7 +178,24 @@ impl ContextMenuUpdate for LibraryBookContextMenu {
 
     self
       .menu
-      .set_item_checked(&Item::MarkAsUnread.to_menu_id(), !ctx.book.read)
+      .set_item_checked(&Item::MarkAsUnread.to_menu_id(), !ctx.book.read)?;
+
+    // Collections may have been created or removed since the menu was built.
+    if let Some(item) = self.menu.get(COLLECTION_MENU_ID)
+      && let Some(submenu) = item.as_submenu()
+    {
+      while submenu.remove_at(0)?.is_some() {}
+      CollectionMenu::append_items(self.menu.app_handle(), submenu, ctx)?;
+    }
+
+    if let Some(item) = self.menu.get(TAG_MENU_ID)
+      && let Some(submenu) = item.as_submenu()
+    {
+      while submenu.remove_at(0)?.is_some() {}
+      TagMenu::append_items(self.menu.app_handle(), submenu, ctx)?;
+    }
+
+    Ok(())
   }
 }
 
@@ -124,7 +218,74 @@ impl MarkAsMenu {
   }
 }
 
-impl_deref_menu!(MarkAsMenu);
+struct CollectionMenu(Submenu<Wry>);
+
+impl CollectionMenu {
+  fn new<M: Manager<Wry>>(app: &M, ctx: &Context) -> Result<Self> {
+    let submenu = SubmenuBuilder::with_id(app, COLLECTION_MENU_ID, "Add to collection").build()?;
+    Self::append_items(app, &submenu, ctx)?;
+    Ok(Self(submenu))
+  }
+
+  fn append_items<M: Manager<Wry>>(app: &M, submenu: &Submenu<Wry>, ctx: &Context) -> Result<()> {
+    // Smart collections decide their books by themselves.
+    let collections = ctx
+      .collections
+      .iter()
+      .filter(|it| !it.is_smart())
+      .collect_vec();
+
+    if collections.is_empty() {
+      let item = MenuItemBuilder::new("No collections")
+        .enabled(false)
+        .build(app)?;
+
+      return submenu.append(&item).map_err(Into::into);
+    }
+
+    for collection in collections {
+      let item = CheckMenuItemBuilder::with_id(collection_menu_id(collection.id), &collection.name)
+        .checked(ctx.book_collections.contains(&collection.id))
+        .build(app)?;
+
+      submenu.append(&item)?;
+    }
+
+    Ok(())
+  }
+}
+
+struct TagMenu(Submenu<Wry>);
+
+impl TagMenu {
+  fn new<M: Manager<Wry>>(app: &M, ctx: &Context) -> Result<Self> {
+    let submenu = SubmenuBuilder::with_id(app, TAG_MENU_ID, "Tags").build()?;
+    Self::append_items(app, &submenu, ctx)?;
+    Ok(Self(submenu))
+  }
+
+  fn append_items<M: Manager<Wry>>(app: &M, submenu: &Submenu<Wry>, ctx: &Context) -> Result<()> {
+    if ctx.tags.is_empty() {
+      let item = MenuItemBuilder::new("No tags")
+        .enabled(false)
+        .build(app)?;
+
+      return submenu.append(&item).map_err(Into::into);
+    }
+
+    for tag in &ctx.tags {
+      let item = CheckMenuItemBuilder::with_id(tag_menu_id(tag.id), &tag.name)
+        .checked(ctx.book_tags.contains(&tag.id))
+        .build(app)?;
+
+      submenu.append(&item)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl_deref_menu!(MarkAsMenu, CollectionMenu, TagMenu);
 
 async fn mark_as_read(app: &AppHandle, read: bool) {
   let id = LibraryBookContextMenu::context(app).book.id;
@@ -135,6 +296,38 @@ async fn mark_as_read(app: &AppHandle, read: bool) {
     .into_err_dialog(app);
 }
 
+/// Add the book to the collection, or remove it if it's already there.
+async fn toggle_collection(app: &AppHandle, collection_id: i32) {
+  let ctx = LibraryBookContextMenu::context(app);
+  let handle = app.database_handle();
+  let result = if ctx.book_collections.contains(&collection_id) {
+    handle
+      .remove_from_collection(collection_id, ctx.book.id)
+      .await
+  } else {
+    handle
+      .add_to_collection(collection_id, ctx.book.id)
+      .await
+  };
+
+  result.map(drop).into_err_dialog(app);
+}
+
+/// Give the book the tag, or take it away if the book already has it.
+async fn toggle_tag(app: &AppHandle, tag_id: i32) {
+  let ctx = LibraryBookContextMenu::context(app);
+  let handle = app.database_handle();
+  let result = if ctx.book_tags.contains(&tag_id) {
+    handle
+      .remove_tag_from_book(tag_id, ctx.book.id)
+      .await
+  } else {
+    handle.add_tag_to_book(tag_id, ctx.book.id).await
+  };
+
+  result.into_err_dialog(app);
+}
+
 async fn open_book(app: &AppHandle) {
   let ctx = LibraryBookContextMenu::context(app);
   if let Ok(book) = ActiveBook::from_model(app, &ctx.book) {
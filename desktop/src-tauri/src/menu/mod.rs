@@ -8,8 +8,8 @@ mod prelude {
   pub(super) use crate::{impl_deref_menu, menu_item_or_bail, mi, popup_context_menu};
   pub(super) use strum::{Display, EnumString};
   pub(super) use tauri::menu::{
-    CheckMenuItemBuilder, Menu, MenuBuilder, MenuEvent, MenuId, MenuItemKind, PredefinedMenuItem,
-    Submenu, SubmenuBuilder,
+    CheckMenuItem, CheckMenuItemBuilder, Menu, MenuBuilder, MenuEvent, MenuId, MenuItemBuilder,
+    MenuItemKind, PredefinedMenuItem, Submenu, SubmenuBuilder,
   };
 }
 
@@ -2,6 +2,7 @@ use crate::book::ActiveBook;
 use crate::menu::prelude::*;
 use crate::menu::Listener;
 use crate::prelude::*;
+use crate::reader::Adjacent;
 use crate::{library, reader};
 use tauri::menu::MenuId;
 
@@ -13,7 +14,11 @@ pub enum Item {
   CloseAll,
   CloseOthers,
   CopyBookPathToClipboard,
+  MarkReadOnNextBook,
+  NextBook,
   OpenBookFolder,
+  PreviousBook,
+  UndoDeletePage,
 }
 
 impl Item {
@@ -58,7 +63,11 @@ impl Listener for Item {
           Item::CloseAll => close_all_reader_windows(&app).await,
           Item::CloseOthers => close_other_reader_windows(&app, window_id).await,
           Item::CopyBookPathToClipboard => copy_path_to_clipboard(&app, window_id).await,
+          Item::MarkReadOnNextBook => toggle_mark_read_on_next_book(&app).await,
+          Item::NextBook => open_adjacent(&app, window_id, Adjacent::Next).await,
           Item::OpenBookFolder => open_book_folder(&app, window_id).await,
+          Item::PreviousBook => open_adjacent(&app, window_id, Adjacent::Previous).await,
+          Item::UndoDeletePage => undo_delete_page(&app, window_id).await,
         }
       };
     });
@@ -80,6 +89,8 @@ impl ReaderMenu {
   pub fn build<M: Manager<Wry>>(app: &M, window_id: u16) -> Result<Menu<Wry>> {
     let menu = Menu::new(app)?;
     menu.append(&*FileMenu::new(app, window_id)?)?;
+    menu.append(&*EditMenu::new(app, window_id)?)?;
+    menu.append(&*GoMenu::new(app, window_id)?)?;
 
     Ok(menu)
   }
@@ -92,6 +103,7 @@ impl ReaderMenu {
   pub async fn update(app: &AppHandle) -> Result<()> {
     let windows = app.reader_windows();
     let windows = windows.read().await;
+    let mark_read = reader::is_mark_read_enabled(app)?;
 
     for window in windows.values() {
       let menu = window.menu(app)?;
@@ -102,6 +114,9 @@ impl ReaderMenu {
 
       let item = Item::CloseOthers.to_menu_id(window.id);
       menu.set_item_enabled(&item, windows.len() > 1)?;
+
+      let item = Item::MarkReadOnNextBook.to_menu_id(window.id);
+      menu.set_item_checked(&item, mark_read)?;
     }
 
     Ok(())
@@ -143,6 +158,47 @@ impl FileMenu {
 
 impl_deref_menu!(FileMenu);
 
+struct EditMenu(Submenu<Wry>);
+
+impl EditMenu {
+  fn new<M: Manager<Wry>>(app: &M, window_id: u16) -> Result<Self> {
+    SubmenuBuilder::new(app, "Edit")
+      .items(&[&mi!(app, UndoDeletePage, window_id, "Undo delete")?])
+      .build()
+      .map(Self)
+      .map_err(Into::into)
+  }
+}
+
+impl_deref_menu!(EditMenu);
+
+struct GoMenu(Submenu<Wry>);
+
+impl GoMenu {
+  fn new<M: Manager<Wry>>(app: &M, window_id: u16) -> Result<Self> {
+    let mark_read = reader::is_mark_read_enabled(app.app_handle())?;
+    let mark_read = CheckMenuItemBuilder::with_id(
+      Item::MarkReadOnNextBook.to_menu_id(window_id),
+      "Mark as read on next book",
+    )
+    .checked(mark_read)
+    .build(app)?;
+
+    SubmenuBuilder::new(app, "Go")
+      .items(&[
+        &mi!(app, NextBook, window_id, "Next book")?,
+        &mi!(app, PreviousBook, window_id, "Previous book")?,
+      ])
+      .separator()
+      .items(&[&mark_read])
+      .build()
+      .map(Self)
+      .map_err(Into::into)
+  }
+}
+
+impl_deref_menu!(GoMenu);
+
 async fn add_to_library(app: &AppHandle, window_id: u16) {
   if let Some(path) = reader::get_book_path(app, window_id).await {
     let result: Result<()> = try {
@@ -195,8 +251,30 @@ async fn copy_path_to_clipboard(app: &AppHandle, window_id: u16) {
   }
 }
 
+async fn open_adjacent(app: &AppHandle, window_id: u16, adjacent: Adjacent) {
+  reader::open_adjacent(app, window_id, adjacent)
+    .await
+    .into_err_dialog(app);
+}
+
+async fn toggle_mark_read_on_next_book(app: &AppHandle) {
+  let result: Result<()> = try {
+    let enabled = !reader::is_mark_read_enabled(app)?;
+    reader::set_mark_read_enabled(app, enabled)?;
+    ReaderMenu::update(app).await?;
+  };
+
+  result.into_err_dialog(app);
+}
+
 async fn open_book_folder(app: &AppHandle, window_id: u16) {
   if let Some(path) = reader::get_book_path(app, window_id).await {
     path.open_parent_detached().into_err_dialog(app);
   }
 }
+
+async fn undo_delete_page(app: &AppHandle, window_id: u16) {
+  reader::undo_delete_page(app, window_id)
+    .await
+    .into_err_dialog(app);
+}
@@ -10,6 +10,9 @@ pub trait PathExt {
   /// Open the parent directory of the path with the default application using a detached process.
   fn open_parent_detached(&self) -> Result<()>;
 
+  /// Move the path from under `from` to under `to`, keeping whatever follows the prefix.
+  fn try_rebase(&self, from: &Path, to: &Path) -> Result<PathBuf>;
+
   fn try_parent(&self) -> Result<&Path>;
   fn try_str(&self) -> Result<&str>;
   fn try_string(&self) -> Result<String>;
@@ -25,6 +28,20 @@ impl<P: AsRef<Path>> PathExt for P {
     self.try_parent()?.open_detached()
   }
 
+  fn try_rebase(&self, from: &Path, to: &Path) -> Result<PathBuf> {
+    let path = self.as_ref();
+    let suffix = path
+      .strip_prefix(from)
+      .map_err(|_| err!(InvalidPath, "{}", path.display()))?;
+
+    // Joining an empty path would leave a trailing separator.
+    if suffix.as_os_str().is_empty() {
+      Ok(to.to_owned())
+    } else {
+      Ok(to.join(suffix))
+    }
+  }
+
   fn try_parent(&self) -> Result<&Path> {
     let path = self.as_ref();
     path
@@ -47,6 +64,10 @@ impl<P: AsRef<Path>> PathExt for P {
 pub trait PathResolverExt {
   fn cover(&self, book_id: i32) -> Result<PathBuf>;
   fn cover_dir(&self) -> Result<PathBuf>;
+  fn database(&self) -> Result<PathBuf>;
+  fn database_backup_dir(&self) -> Result<PathBuf>;
+  fn sidecar_dir(&self) -> Result<PathBuf>;
+  fn trash_dir(&self) -> Result<PathBuf>;
 
   #[cfg(feature = "devtools")]
   fn dev_cache_dir(&self) -> Result<PathBuf>;
@@ -68,6 +89,39 @@ impl PathResolverExt for PathResolver<Wry> {
       .map_err(Into::into)
   }
 
+  fn database(&self) -> Result<PathBuf> {
+    #[cfg(feature = "devtools")]
+    let name = "kotori-dev.db";
+    #[cfg(not(feature = "devtools"))]
+    let name = "kotori.db";
+
+    self
+      .app_local_data_dir()
+      .map(|it| it.join(name))
+      .map_err(Into::into)
+  }
+
+  fn database_backup_dir(&self) -> Result<PathBuf> {
+    self
+      .app_local_data_dir()
+      .map(|it| it.join("backups"))
+      .map_err(Into::into)
+  }
+
+  fn sidecar_dir(&self) -> Result<PathBuf> {
+    self
+      .app_local_data_dir()
+      .map(|it| it.join("metadata"))
+      .map_err(Into::into)
+  }
+
+  fn trash_dir(&self) -> Result<PathBuf> {
+    self
+      .app_local_data_dir()
+      .map(|it| it.join("trash"))
+      .map_err(Into::into)
+  }
+
   #[cfg(feature = "devtools")]
   fn dev_cache_dir(&self) -> Result<PathBuf> {
     self
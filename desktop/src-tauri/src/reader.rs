@@ -1,15 +1,27 @@
-use crate::book::ActiveBook;
+use crate::book::{ActiveBook, MetadataStorage};
 use crate::event::Event;
 use crate::menu::ReaderMenu;
 use crate::prelude::*;
 use crate::utils::collections::OrderedMap;
+use crate::utils::glob;
 use crate::window::{ReaderWindow, WindowExt};
+use crate::{bail, trash};
 use std::sync::Arc;
 use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tokio::sync::{oneshot, RwLock};
 
 pub type WindowMap = Arc<RwLock<OrderedMap<u16, ReaderWindow>>>;
 
+/// Whether the book should be marked as read when moving on to the next one.
+const MARK_READ_KEY: &str = "markReadOnNextBook";
+
+/// Which way to go from the book currently open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Adjacent {
+  Next,
+  Previous,
+}
+
 #[derive(Default)]
 pub struct Reader {
   windows: WindowMap,
@@ -41,7 +53,9 @@ pub async fn open_book(app: &AppHandle, book: ActiveBook) -> Result<()> {
   }
 
   // Otherwise, open a new window.
-  let window = ReaderWindow::open(app, book)?;
+  let mut window = ReaderWindow::open(app, book)?;
+  window.start_session(app).await.into_err_log(app);
+
   let windows = app.reader_windows();
   let mut windows = windows.write().await;
   windows.insert(window.id, window);
@@ -168,6 +182,163 @@ pub async fn switch_focus(app: &AppHandle) -> Result<()> {
   Ok(())
 }
 
+/// Replace the book in the window with the one next to it, if there's any.
+///
+/// Books in a series follow its order. Otherwise, the files in the same folder are
+/// taken in natural order, which is also how books outside the library are handled.
+pub async fn open_adjacent(app: &AppHandle, window_id: u16, adjacent: Adjacent) -> Result<()> {
+  // The book itself isn't cloned, as dropping an `ActiveBook` closes its file.
+  let windows = app.reader_windows();
+  let windows_guard = windows.read().await;
+  let Some(window) = windows_guard.get(&window_id) else {
+    return Ok(());
+  };
+
+  let path = window.book.path.clone();
+  let id = window.book.try_id().await.ok();
+  drop(windows_guard);
+
+  let Some(book) = find_adjacent(app, &path, id, adjacent).await? else {
+    return Ok(());
+  };
+
+  if adjacent == Adjacent::Next
+    && let Some(id) = id
+    && is_mark_read_enabled(app)?
+  {
+    app
+      .database_handle()
+      .update_book_read(id, true)
+      .await?;
+  }
+
+  let mut windows = windows.write().await;
+  if let Some(window) = windows.get_mut(&window_id) {
+    window.set_book(app, book).await?;
+
+    drop(windows);
+    ReaderMenu::update(app).await?;
+  }
+
+  Ok(())
+}
+
+async fn find_adjacent(
+  app: &AppHandle,
+  path: &Path,
+  id: Option<i32>,
+  adjacent: Adjacent,
+) -> Result<Option<ActiveBook>> {
+  if let Some(id) = id {
+    let database = app.database_handle();
+    if database.get_book_series(id).await?.is_some() {
+      let model = match adjacent {
+        Adjacent::Next => database.get_next_in_series(id).await?,
+        Adjacent::Previous => database.get_previous_in_series(id).await?,
+      };
+
+      return model
+        .map(|model| ActiveBook::from_model(app, &model))
+        .transpose();
+    }
+  }
+
+  let path = path.to_owned();
+  let sibling = spawn_blocking(move || find_sibling(&path, adjacent)).await??;
+  sibling
+    .map(|path| ActiveBook::new(app, path))
+    .transpose()
+}
+
+/// Book next to this one in its folder, sorted the way a file manager would.
+fn find_sibling(path: &Path, adjacent: Adjacent) -> Result<Option<PathBuf>> {
+  let Some(parent) = path.parent() else {
+    return Ok(None);
+  };
+
+  let globset = glob::book();
+  let siblings = std::fs::read_dir(parent)?
+    .filter_map(|entry| entry.ok().map(|it| it.path()))
+    .filter(|it| it.is_file() && globset.is_match(it))
+    .sorted_by(|a, b| {
+      let a = a.to_string_lossy();
+      let b = b.to_string_lossy();
+      natord::compare_ignore_case(&a, &b)
+    })
+    .collect_vec();
+
+  let sibling = siblings
+    .iter()
+    .position(|it| it == path)
+    .and_then(|index| match adjacent {
+      Adjacent::Next => index.checked_add(1),
+      Adjacent::Previous => index.checked_sub(1),
+    })
+    .and_then(|index| siblings.get(index).cloned());
+
+  Ok(sibling)
+}
+
+pub fn is_mark_read_enabled(app: &AppHandle) -> Result<bool> {
+  use tauri_plugin_pinia::ManagerExt;
+
+  app
+    .with_store("config", |store| {
+      let enabled = store
+        .get(MARK_READ_KEY)
+        .and_then(|it| it.as_bool())
+        .unwrap_or(false);
+
+      Ok(enabled)
+    })
+    .map_err(Into::into)
+}
+
+pub fn set_mark_read_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+  use tauri_plugin_pinia::ManagerExt;
+
+  app
+    .with_store("config", |store| store.set(MARK_READ_KEY, enabled.into()))
+    .map_err(Into::into)
+}
+
+/// Remember the page the reader is on, marking the book as read once the last page is reached.
+pub async fn update_progress(app: &AppHandle, window_id: u16, index: usize) -> Result<()> {
+  let windows = app.reader_windows();
+  if let Some(window) = windows.write().await.get_mut(&window_id) {
+    window.view_page(index);
+  }
+
+  let windows = windows.read().await;
+  let Some(window) = windows.get(&window_id) else {
+    return Ok(());
+  };
+
+  // Books outside the library have nowhere to keep their progress.
+  let Ok(id) = window.book.try_id().await else {
+    return Ok(());
+  };
+
+  let pages = window.book.pages().await?;
+  drop(windows);
+
+  let name = pages
+    .get(&index)
+    .ok_or_else(|| err!(PageNotFound, "{index}"))?;
+
+  let database = app.database_handle();
+  let book = database
+    .update_book_progress(id, index, name)
+    .await?;
+
+  let is_last = pages.keys().max() == Some(&index);
+  if is_last && !book.read {
+    database.update_book_read(id, true).await?;
+  }
+
+  Ok(())
+}
+
 pub async fn delete_page(app: &AppHandle, window_id: u16, name: &str) -> Result<()> {
   let windows = app.reader_windows();
   let mut windows = windows.write().await;
@@ -187,7 +358,23 @@ pub async fn delete_page(app: &AppHandle, window_id: u16, name: &str) -> Result<
   Ok(())
 }
 
+/// Put back the page most recently deleted from the book open in the window.
+pub async fn undo_delete_page(app: &AppHandle, window_id: u16) -> Result<()> {
+  if let Some(path) = get_book_path(app, window_id).await {
+    trash::restore_latest(app, &path).await?;
+  }
+
+  Ok(())
+}
+
 pub async fn delete_page_with_dialog(app: &AppHandle, window_id: u16, name: &str) -> Result<()> {
+  // There's no point in asking if the book can't be modified anyway.
+  if MetadataStorage::get(app)?.is_sidecar()
+    && let Some(path) = get_book_path(app, window_id).await
+  {
+    bail!(ReadOnlyBook, "{}", path.display());
+  }
+
   let (tx, rx) = oneshot::channel();
   app
     .dialog()
@@ -0,0 +1,165 @@
+use crate::book::ActiveBook;
+use crate::database::model::book::ADDED_AT_FORMAT;
+use crate::database::model::{NewTrashedPage, TrashedPage};
+use crate::event::Event;
+use crate::prelude::*;
+use chrono::Utc;
+use std::io::ErrorKind;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tokio::fs;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Keep a copy of the page before it's deleted from the book, so that it can be restored later.
+pub async fn put(
+  app: &AppHandle,
+  book_path: &Path,
+  page_name: &str,
+  page_index: usize,
+  was_cover: bool,
+  bytes: &[u8],
+) -> Result<TrashedPage> {
+  let dir = app.path().trash_dir()?;
+  fs::create_dir_all(&dir).await?;
+
+  let file_name = Uuid::now_v7().to_string();
+  let path = dir.join(&file_name);
+  fs::write(&path, bytes).await?;
+
+  let page = NewTrashedPage {
+    book_path: book_path.try_string()?,
+    page_name: page_name.to_owned(),
+    page_index: i32::try_from(page_index)?,
+    was_cover,
+    file_name,
+    size: i64::try_from(bytes.len())?,
+    deleted_at: Utc::now().format(ADDED_AT_FORMAT).to_string(),
+  };
+
+  let result = app.database_handle().add_to_trash(page).await;
+  if result.is_err() {
+    let _ = fs::remove_file(&path).await;
+  }
+
+  result
+}
+
+pub async fn get_all(app: &AppHandle) -> Result<Vec<TrashedPage>> {
+  app
+    .database_handle()
+    .get_all_trashed_pages()
+    .await
+}
+
+pub async fn read(app: &AppHandle, page: &TrashedPage) -> Result<Vec<u8>> {
+  let path = app.path().trash_dir()?.join(&page.file_name);
+  fs::read(path).await.map_err(Into::into)
+}
+
+/// Put the page back in its book, removing it from the trash.
+/// A reader window with the book open is reloaded to show it again.
+pub async fn restore(app: &AppHandle, trash_id: i32) -> Result<()> {
+  let page = app
+    .database_handle()
+    .get_trashed_page(trash_id)
+    .await?;
+
+  // Rewriting the book may take a while, so it's done without holding the reader windows.
+  let path = PathBuf::from(&page.book_path);
+  ActiveBook::new(app, &path)?
+    .restore_page(&page)
+    .await?;
+
+  let windows = app.reader_windows();
+  let mut windows = windows.write().await;
+
+  let window_id = windows
+    .values()
+    .find(|it| it.book.path == path)
+    .map(|it| it.id);
+
+  if let Some(window_id) = window_id
+    && let Some(window) = windows.get_mut(&window_id)
+  {
+    window.book.reset_pages();
+    Event::ReaderBookChanged { window_id }.emit(app)?;
+  }
+
+  drop(windows);
+
+  #[cfg(feature = "tracing")]
+  info!("page {} restored to {}", page.page_name, page.book_path);
+
+  remove(app, &page).await
+}
+
+/// Restore the page most recently deleted from the book, if there's one.
+pub async fn restore_latest(app: &AppHandle, book_path: &Path) -> Result<()> {
+  let latest = app
+    .database_handle()
+    .get_latest_trashed_page(book_path)
+    .await?;
+
+  if let Some(page) = latest {
+    restore(app, page.id).await?;
+  }
+
+  Ok(())
+}
+
+/// Delete the page for good.
+pub async fn purge(app: &AppHandle, trash_id: i32) -> Result<()> {
+  let page = app
+    .database_handle()
+    .get_trashed_page(trash_id)
+    .await?;
+
+  remove(app, &page).await
+}
+
+pub async fn empty(app: &AppHandle) -> Result<()> {
+  let pages = app.database_handle().empty_trash().await?;
+  let dir = app.path().trash_dir()?;
+  for page in pages {
+    remove_file(&dir.join(&page.file_name)).await?;
+  }
+
+  Ok(())
+}
+
+pub async fn empty_with_dialog(app: &AppHandle) -> Result<()> {
+  let (tx, rx) = oneshot::channel();
+  app
+    .dialog()
+    .message("Every page in the trash will be deleted for good.")
+    .title("Empty trash")
+    .kind(MessageDialogKind::Warning)
+    .ok_button_label("Empty")
+    .cancel_button_label("Cancel")
+    .show(move |response| {
+      let _ = tx.send(response);
+    });
+
+  if rx.await? {
+    empty(app).await?;
+  }
+
+  Ok(())
+}
+
+async fn remove(app: &AppHandle, page: &TrashedPage) -> Result<()> {
+  let path = app.path().trash_dir()?.join(&page.file_name);
+  remove_file(&path).await?;
+  app
+    .database_handle()
+    .remove_from_trash(page.id)
+    .await
+}
+
+/// The file may already be gone, which is just as good.
+async fn remove_file(path: &Path) -> Result<()> {
+  match fs::remove_file(path).await {
+    Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+    _ => Ok(()),
+  }
+}
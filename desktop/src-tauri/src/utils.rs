@@ -18,7 +18,9 @@ pub mod glob {
 
   pub fn book() -> GlobSet {
     GlobSetBuilder::new()
+      .add(glob("*.cb7"))
       .add(glob("*.cbr"))
+      .add(glob("*.cbt"))
       .add(glob("*.cbz"))
       .add(glob("*.zip"))
       .build()
@@ -1,5 +1,6 @@
 use super::{ColorMode, WindowKind};
 use crate::book::ActiveBook;
+use crate::manager::ManagerExt;
 use crate::menu::AppMenu;
 use crate::reader;
 use crate::result::{Result, ResultExt};
@@ -54,6 +55,7 @@ fn on_window_event(app: &AppHandle) -> impl Fn(&WindowEvent) {
       #[cfg(feature = "tracing")]
       tracing::info!("main window destroyed, exiting");
 
+      // Metadata waiting to be written is flushed once the exit is requested.
       app.exit(0);
     }
     WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) => {
@@ -4,12 +4,21 @@ use crate::event::Event;
 use crate::menu::ReaderMenu;
 use crate::prelude::*;
 use crate::utils::glob;
+use ahash::HashSet;
+use std::mem;
 use tauri::menu::{Menu, MenuEvent};
 use tauri::{DragDropEvent, WebviewWindowBuilder, WindowEvent};
 
 pub struct ReaderWindow {
   pub id: u16,
   pub book: ActiveBook,
+  session: Option<Session>,
+}
+
+/// Reading session of the book currently open in the window.
+struct Session {
+  id: i32,
+  pages: HashSet<usize>,
 }
 
 impl ReaderWindow {
@@ -47,7 +56,7 @@ impl ReaderWindow {
     #[cfg(feature = "open-reader-devtools")]
     window.open_devtools();
 
-    Ok(ReaderWindow { id: window_id, book })
+    Ok(ReaderWindow { id: window_id, book, session: None })
   }
 
   pub fn webview_window(&self, app: &AppHandle) -> Option<WebviewWindow> {
@@ -61,12 +70,42 @@ impl ReaderWindow {
       .ok_or_else(|| err!(WindowMenuNotFound))
   }
 
-  fn set_book(&mut self, app: &AppHandle, book: ActiveBook) -> Result<()> {
+  /// Start a reading session for the current book, if it's in the library.
+  pub async fn start_session(&mut self, app: &AppHandle) -> Result<()> {
+    let Ok(book_id) = self.book.try_id().await else {
+      return Ok(());
+    };
+
+    let session = app
+      .database_handle()
+      .start_reading_session(book_id)
+      .await?;
+
+    let pages = HashSet::default();
+    self.session = Some(Session { id: session.id, pages });
+
+    Ok(())
+  }
+
+  /// Count the page as viewed in the current session.
+  pub fn view_page(&mut self, index: usize) {
+    if let Some(session) = &mut self.session {
+      session.pages.insert(index);
+    }
+  }
+
+  /// Swap the book in the window, finishing the reading session of the previous one.
+  pub async fn set_book(&mut self, app: &AppHandle, book: ActiveBook) -> Result<()> {
     if let Some(webview) = self.webview_window(app) {
       webview.set_title(book.title.as_str())?;
     };
 
-    self.book = book;
+    let previous = mem::replace(&mut self.book, book);
+    let session = self.session.take();
+    let handle = app.clone();
+    spawn(async move { finish_reading(&handle, previous, session).await });
+
+    self.start_session(app).await.into_err_log(app);
 
     Event::ReaderBookChanged { window_id: self.id }.emit(app)
   }
@@ -120,6 +159,7 @@ fn on_window_event(app: &AppHandle, window_id: u16) -> impl Fn(&WindowEvent) {
 /// When a reader window is closed, it should be removed from the list of windows.
 /// After that, all remaining windows should have their menus updated to reflect this change.
 /// The previous reader window should also be focused, or the main window if there are no more reader windows left.
+/// Lastly, the reading session is ended.
 fn handle_close_requested_event(app: &AppHandle, window_id: u16) {
   let app = app.clone();
   spawn(async move {
@@ -131,7 +171,7 @@ fn handle_close_requested_event(app: &AppHandle, window_id: u16) {
       .and_then(|id| id.checked_sub(1))
       .unwrap_or(0);
 
-    windows.shift_remove(&window_id);
+    let removed = windows.shift_remove(&window_id);
     drop(windows);
 
     ReaderMenu::spawn_update(&app);
@@ -145,9 +185,27 @@ fn handle_close_requested_event(app: &AppHandle, window_id: u16) {
       .map(|webview| webview.set_foreground_focus())
       .transpose()
       .into_err_log(&app);
+
+    if let Some(window) = removed {
+      finish_reading(&app, window.book, window.session).await;
+    }
   });
 }
 
+/// End the reading session, if any, and write the progress to the book metadata.
+/// The book is only dropped, and thus closed, after that.
+async fn finish_reading(app: &AppHandle, book: ActiveBook, session: Option<Session>) {
+  if let Some(session) = session {
+    app
+      .database_handle()
+      .end_reading_session(session.id, session.pages.len())
+      .await
+      .into_err_log(app);
+  }
+
+  book.save_progress().await.into_err_log(app);
+}
+
 /// Check for books among the dropped files and open the first one found.
 /// If a book is opened successfully, update the menus for all reader windows.
 fn handle_drop_event(app: &AppHandle, window_id: u16, paths: &[PathBuf]) {
@@ -167,7 +225,7 @@ fn handle_drop_event(app: &AppHandle, window_id: u16, paths: &[PathBuf]) {
 
         if let Some(window) = windows.get_mut(&window_id) {
           let book = ActiveBook::new(&app, path)?;
-          window.set_book(&app, book)?;
+          window.set_book(&app, book).await?;
 
           drop(windows);
           ReaderMenu::update(&app).await?;
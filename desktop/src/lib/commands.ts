@@ -1,52 +1,227 @@
 export const enum Command {
+  AddTagToBook = 'add_tag_to_book',
+  AddToCollection = 'add_to_collection',
   AddToLibraryWithDialog = 'add_to_library_with_dialog',
+  BackupDatabase = 'backup_database',
   CloseWindow = 'close_window',
+  CreateCollection = 'create_collection',
+  CreateSmartCollection = 'create_smart_collection',
+  CreateTag = 'create_tag',
   DeletePageWithDialog = 'delete_page_with_dialog',
+  EmptyTrashWithDialog = 'empty_trash_with_dialog',
+  ExportLibrary = 'export_library',
+  ExportLibraryWithDialog = 'export_library_with_dialog',
   FocusMainWindow = 'focus_main_window',
+  GetAllSeries = 'get_all_series',
+  GetBookCacheStats = 'get_book_cache_stats',
+  GetBookTags = 'get_book_tags',
+  GetCollectionBooks = 'get_collection_books',
   GetCollections = 'get_collections',
   GetCurrentReaderBook = 'get_current_reader_book',
+  GetDatabaseBackups = 'get_database_backups',
+  CheckLibraryFiles = 'check_library_files',
+  GetDuplicateBooks = 'get_duplicate_books',
   GetLibraryBooks = 'get_library_books',
+  GetLibraryFolders = 'get_library_folders',
+  GetNextInSeries = 'get_next_in_series',
+  GetPreviousInSeries = 'get_previous_in_series',
+  GetReadingHistory = 'get_reading_history',
+  GetRecentlyRead = 'get_recently_read',
+  GetSeriesBooks = 'get_series_books',
+  GetSeriesPatterns = 'get_series_patterns',
+  GetTags = 'get_tags',
+  GetTrashedPages = 'get_trashed_pages',
+  ImportLibrary = 'import_library',
+  ImportLibraryWithDialog = 'import_library_with_dialog',
   OpenBook = 'open_book',
   OpenBookWithDialog = 'open_book_with_dialog',
+  OpenNextBook = 'open_next_book',
+  OpenPreviousBook = 'open_previous_book',
+  PurgeTrashedPage = 'purge_trashed_page',
+  QueryLibrary = 'query_library',
+  RelinkLibraryFolder = 'relink_library_folder',
+  RelinkLibraryFolderWithDialog = 'relink_library_folder_with_dialog',
   RemoveBook = 'remove_book',
   RemoveBookWithDialog = 'remove_book_with_dialog',
+  RemoveCollection = 'remove_collection',
+  RemoveFromCollection = 'remove_from_collection',
+  RemoveLibraryFolder = 'remove_library_folder',
+  RemoveLibraryFolderWithDialog = 'remove_library_folder_with_dialog',
+  RemoveTag = 'remove_tag',
+  RemoveTagFromBook = 'remove_tag_from_book',
+  RenameCollection = 'rename_collection',
+  RenameTag = 'rename_tag',
+  RestoreDatabaseBackup = 'restore_database_backup',
+  RestoreDatabaseBackupWithDialog = 'restore_database_backup_with_dialog',
+  RestoreTrashedPage = 'restore_trashed_page',
   ServerPort = 'server_port',
+  SetBookTags = 'set_book_tags',
+  SetFolderExclude = 'set_folder_exclude',
+  SetSeriesPatterns = 'set_series_patterns',
+  SearchBooks = 'search_books',
   ShowLibraryBookContextMenu = 'show_library_book_context_menu',
   ShowReaderPageContextMenu = 'show_reader_page_context_menu',
   ShowWindow = 'show_window',
   SwitchReaderFocus = 'switch_reader_focus',
   ToggleFullscreen = 'toggle_fullscreen',
+  UndoDeletePage = 'undo_delete_page',
   UpdateBookRating = 'update_book_rating',
+  UpdateReaderProgress = 'update_reader_progress',
+  UpdateSmartCollection = 'update_smart_collection',
+}
+
+export async function addTagToBook(tagId: number, bookId: number) {
+  await invoke(Command.AddTagToBook, { tagId, bookId });
+}
+
+export async function addToCollection(collectionId: number, bookId: number) {
+  await invoke(Command.AddToCollection, { collectionId, bookId });
 }
 
 export async function addToLibraryWithDialog() {
   await invoke(Command.AddToLibraryWithDialog);
 }
 
+export function backupDatabase() {
+  return invoke<DatabaseBackup>(Command.BackupDatabase);
+}
+
 export function closeWindow() {
   invoke(Command.CloseWindow).catch(handleError);
 }
 
+export function createCollection(name: string) {
+  return invoke<BookCollection>(Command.CreateCollection, { name });
+}
+
+export function createSmartCollection(name: string, filter: SmartFilter) {
+  return invoke<BookCollection>(Command.CreateSmartCollection, { name, filter });
+}
+
+export function createTag(name: string) {
+  return invoke<BookTag>(Command.CreateTag, { name });
+}
+
 export async function deletePageWithDialog(windowId: number, name: string) {
   await invoke(Command.DeletePageWithDialog, { windowId, name });
 }
 
+export async function emptyTrashWithDialog() {
+  await invoke(Command.EmptyTrashWithDialog);
+}
+
+export async function exportLibrary(path: string) {
+  await invoke(Command.ExportLibrary, { path });
+}
+
+export async function exportLibraryWithDialog() {
+  await invoke(Command.ExportLibraryWithDialog);
+}
+
 export function focusMainWindow() {
   invoke(Command.FocusMainWindow).catch(handleError);
 }
 
+export function getAllSeries() {
+  return invoke<LibrarySeries[]>(Command.GetAllSeries);
+}
+
+export function getBookCacheStats() {
+  return invoke<BookCacheStats>(Command.GetBookCacheStats);
+}
+
+export function getBookTags(bookId: number) {
+  return invoke<BookTag[]>(Command.GetBookTags, { id: bookId });
+}
+
+export function getCollectionBooks(id: number) {
+  return invoke<LibraryBook[]>(Command.GetCollectionBooks, { id });
+}
+
 export function getCurrentReaderBook(windowId: number) {
   return invoke<ReaderBook>(Command.GetCurrentReaderBook, { windowId });
 }
 
+export function getDatabaseBackups() {
+  return invoke<DatabaseBackup[]>(Command.GetDatabaseBackups);
+}
+
+export async function checkLibraryFiles() {
+  await invoke(Command.CheckLibraryFiles);
+}
+
+export function getDuplicateBooks() {
+  return invoke<LibraryBook[][]>(Command.GetDuplicateBooks);
+}
+
 export function getLibraryBooks() {
   return invoke<LibraryBook[]>(Command.GetLibraryBooks);
 }
 
+export function getLibraryFolders() {
+  return invoke<LibraryFolder[]>(Command.GetLibraryFolders);
+}
+
+export function getNextInSeries(bookId: number) {
+  return invoke<Nullish<LibraryBook>>(Command.GetNextInSeries, { id: bookId });
+}
+
+export function getPreviousInSeries(bookId: number) {
+  return invoke<Nullish<LibraryBook>>(Command.GetPreviousInSeries, { id: bookId });
+}
+
+export function getReadingHistory(bookId: number) {
+  return invoke<ReadingSession[]>(Command.GetReadingHistory, { id: bookId });
+}
+
+export function getRecentlyRead(limit: number) {
+  return invoke<LibraryBook[]>(Command.GetRecentlyRead, { limit });
+}
+
+export function getSeriesBooks(id: number) {
+  return invoke<LibraryBook[]>(Command.GetSeriesBooks, { id });
+}
+
+export function getSeriesPatterns() {
+  return invoke<string[]>(Command.GetSeriesPatterns);
+}
+
+export function getTags() {
+  return invoke<BookTag[]>(Command.GetTags);
+}
+
+export function getTrashedPages() {
+  return invoke<TrashedPage[]>(Command.GetTrashedPages);
+}
+
+export async function importLibrary(path: string, rewrites: PathRewrite[] = []) {
+  await invoke(Command.ImportLibrary, { path, rewrites });
+}
+
+export async function importLibraryWithDialog(rewrites: PathRewrite[] = []) {
+  await invoke(Command.ImportLibraryWithDialog, { rewrites });
+}
+
 export function getServerPort() {
   return invoke<number>(Command.ServerPort);
 }
 
+export async function purgeTrashedPage(id: number) {
+  await invoke(Command.PurgeTrashedPage, { id });
+}
+
+export function queryLibrary(query: LibraryQuery = {}) {
+  return invoke<LibraryPage>(Command.QueryLibrary, { query });
+}
+
+export async function relinkLibraryFolder(id: number, path: string) {
+  await invoke(Command.RelinkLibraryFolder, { id, path });
+}
+
+export async function relinkLibraryFolderWithDialog(id: number) {
+  await invoke(Command.RelinkLibraryFolderWithDialog, { id });
+}
+
 export async function removeBook(id: number) {
   await invoke(Command.RemoveBook, { id });
 }
@@ -56,6 +231,66 @@ export async function removeBookWithDialog(id: Nullish<number>) {
   await invoke(Command.RemoveBookWithDialog, { id });
 }
 
+export async function removeCollection(id: number) {
+  await invoke(Command.RemoveCollection, { id });
+}
+
+export async function removeFromCollection(collectionId: number, bookId: number) {
+  await invoke(Command.RemoveFromCollection, { collectionId, bookId });
+}
+
+export async function removeLibraryFolder(id: number, removeBooks: boolean) {
+  await invoke(Command.RemoveLibraryFolder, { id, removeBooks });
+}
+
+export async function removeLibraryFolderWithDialog(id: number) {
+  await invoke(Command.RemoveLibraryFolderWithDialog, { id });
+}
+
+export async function removeTag(id: number) {
+  await invoke(Command.RemoveTag, { id });
+}
+
+export async function removeTagFromBook(tagId: number, bookId: number) {
+  await invoke(Command.RemoveTagFromBook, { tagId, bookId });
+}
+
+export function renameCollection(id: number, name: string) {
+  return invoke<BookCollection>(Command.RenameCollection, { id, name });
+}
+
+export function renameTag(id: number, name: string) {
+  return invoke<BookTag>(Command.RenameTag, { id, name });
+}
+
+export async function restoreDatabaseBackup(path: string) {
+  await invoke(Command.RestoreDatabaseBackup, { path });
+}
+
+export async function restoreDatabaseBackupWithDialog() {
+  await invoke(Command.RestoreDatabaseBackupWithDialog);
+}
+
+export async function restoreTrashedPage(id: number) {
+  await invoke(Command.RestoreTrashedPage, { id });
+}
+
+export function setBookTags(bookId: number, names: string[]) {
+  return invoke<BookTag[]>(Command.SetBookTags, { id: bookId, names });
+}
+
+export function setFolderExclude(id: number, exclude: string[]) {
+  return invoke<LibraryFolder>(Command.SetFolderExclude, { id, exclude });
+}
+
+export async function setSeriesPatterns(patterns: string[]) {
+  await invoke(Command.SetSeriesPatterns, { patterns });
+}
+
+export function searchBooks(query: string, limit?: number) {
+  return invoke<LibraryBook[]>(Command.SearchBooks, { query, limit });
+}
+
 export function showLibraryBookContextMenu(bookId: number) {
   invoke(Command.ShowLibraryBookContextMenu, { bookId }).catch(handleError);
 }
@@ -80,10 +315,30 @@ export async function openBookWithDialog() {
   await invoke(Command.OpenBookWithDialog);
 }
 
+export async function openNextBook(windowId: number) {
+  await invoke(Command.OpenNextBook, { windowId });
+}
+
+export async function openPreviousBook(windowId: number) {
+  await invoke(Command.OpenPreviousBook, { windowId });
+}
+
 export function toggleFullscreen() {
   invoke(Command.ToggleFullscreen).catch(handleError);
 }
 
+export async function undoDeletePage(windowId: number) {
+  await invoke(Command.UndoDeletePage, { windowId });
+}
+
 export function updateBookRating(bookId: number, rating: number) {
   invoke(Command.UpdateBookRating, { id: bookId, rating }).catch(handleError);
 }
+
+export function updateReaderProgress(windowId: number, index: number) {
+  invoke(Command.UpdateReaderProgress, { windowId, index }).catch(handleError);
+}
+
+export function updateSmartCollection(id: number, filter: SmartFilter) {
+  return invoke<BookCollection>(Command.UpdateSmartCollection, { id, filter });
+}
@@ -2,8 +2,14 @@ import { defineStore } from 'pinia';
 
 export const useConfigStore = defineStore('config', () => {
   const colorMode = useColorMode({ storageKey: null });
+  const keepBookBackup = ref(false);
+  const metadataStorage = ref<'archive' | 'appData' | 'nextToBook'>('archive');
+  const writeComicInfo = ref(false);
 
   return {
     colorMode,
+    keepBookBackup,
+    metadataStorage,
+    writeComicInfo,
   };
 });
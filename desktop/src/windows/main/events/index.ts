@@ -1,10 +1,13 @@
 import { handleError } from 'manatsu';
-import { useLibraryStore } from '../stores';
+import { useCollectionStore, useLibraryStore } from '../stores';
 import { listen } from '@tauri-apps/api/event';
 
 export const enum Event {
   BookAdded = 'book_added',
+  BookMissing = 'book_missing',
   BookRemoved = 'book_removed',
+  CollectionRemoved = 'collection_removed',
+  CollectionUpdated = 'collection_updated',
   CoverExtracted = 'cover_extracted',
   LibraryCleared = 'library_cleared',
   RatingUpdated = 'rating_updated',
@@ -14,7 +17,10 @@ export const enum Event {
 export function setupEventListeners() {
   const promises = Promise.all([
     onBookAdded(),
+    onBookMissing(),
     onBookRemoved(),
+    onCollectionRemoved(),
+    onCollectionUpdated(),
     onCoverExtracted(),
     onLibraryCleared(),
     onRatingUpdated(),
@@ -31,6 +37,13 @@ function onBookAdded() {
   });
 }
 
+function onBookMissing() {
+  return listen<BookMissingPayload>(Event.BookMissing, ({ payload }) => {
+    const store = useLibraryStore();
+    store.library.setBookMissing(payload.id, true);
+  });
+}
+
 function onBookRemoved() {
   return listen<BookRemovedPayload>(Event.BookRemoved, ({ payload }) => {
     const store = useLibraryStore();
@@ -41,6 +54,20 @@ function onBookRemoved() {
   });
 }
 
+function onCollectionRemoved() {
+  return listen<CollectionRemovedPayload>(Event.CollectionRemoved, ({ payload }) => {
+    const store = useCollectionStore();
+    store.remove(payload.id);
+  });
+}
+
+function onCollectionUpdated() {
+  return listen<CollectionUpdatedPayload>(Event.CollectionUpdated, ({ payload }) => {
+    const store = useCollectionStore();
+    store.update(payload);
+  });
+}
+
 function onCoverExtracted() {
   return listen<CoverExtractedPayload>(Event.CoverExtracted, ({ payload }) => {
     const store = useLibraryStore();
@@ -1,11 +1,23 @@
 import * as commands from '@/lib/commands';
 
+const PAGE_SIZE = 500;
+
 class LibraryBookImpl implements LibraryBook {
   public readonly id: number;
   public readonly title: string;
   public readonly path: string;
+  public readonly series: Nullish<string>;
+  public readonly number: Nullish<string>;
+  public readonly volume: Nullish<number>;
+  public readonly summary: Nullish<string>;
+  public readonly writer: Nullish<string>;
+  public readonly language: Nullish<string>;
+  public readonly manga: Nullish<boolean>;
+  public readonly rightToLeft: boolean;
+  public readonly addedAt: Nullish<string>;
 
   #cover: string;
+  #missing: boolean;
   #rating: number;
   #read: boolean;
 
@@ -16,6 +28,16 @@ class LibraryBookImpl implements LibraryBook {
     this.id = book.id;
     this.title = book.title;
     this.path = book.path;
+    this.series = book.series;
+    this.number = book.number;
+    this.volume = book.volume;
+    this.summary = book.summary;
+    this.writer = book.writer;
+    this.language = book.language;
+    this.manga = book.manga;
+    this.rightToLeft = book.rightToLeft;
+    this.addedAt = book.addedAt;
+    this.#missing = book.missing;
     this.#rating = book.rating;
     this.#read = book.read;
 
@@ -38,6 +60,14 @@ class LibraryBookImpl implements LibraryBook {
     }
   }
 
+  get missing() {
+    return this.#missing;
+  }
+
+  set missing(missing: boolean) {
+    this.#missing = missing;
+  }
+
   get rating() {
     return this.#rating;
   }
@@ -78,15 +108,26 @@ export class Library {
     return this.#books.get(id);
   }
 
+  /**
+   * Load the library a page at a time, so large libraries show up without waiting for every book.
+   * Missing files and covers are checked in the background once everything is loaded.
+   */
   public async load() {
-    const books = await commands.getLibraryBooks();
     this.#books.clear();
+    this.#trigger();
 
-    for (const book of books) {
-      this.#books.set(book.id, new LibraryBookImpl(book));
-    }
+    let after: Nullish<LibraryCursor> = null;
+    do {
+      const page = await commands.queryLibrary({ after, limit: PAGE_SIZE });
+      for (const book of page.books) {
+        this.#books.set(book.id, new LibraryBookImpl(book));
+      }
 
-    this.#trigger();
+      this.#trigger();
+      after = page.next;
+    } while (after);
+
+    await commands.checkLibraryFiles();
   }
 
   public remove(id: number) {
@@ -103,6 +144,14 @@ export class Library {
     }
   }
 
+  public setBookMissing(id: number, missing: boolean) {
+    const book = this.#books.get(id);
+    if (book) {
+      book.missing = missing;
+      this.#trigger();
+    }
+  }
+
   public setBookRating(id: number, rating: number) {
     const book = this.#books.get(id);
     if (book) {
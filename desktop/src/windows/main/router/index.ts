@@ -15,6 +15,11 @@ const router = createRouter({
       name: RouteName.Collection,
       component: () => import('../views/Collection.vue'),
     },
+    {
+      path: '/duplicates',
+      name: RouteName.Duplicates,
+      component: () => import('../views/Duplicates.vue'),
+    },
     {
       path: '/tag',
       name: RouteName.BookTag,
@@ -31,8 +36,20 @@ function navigateToLibrary(query?: LocationQueryRaw) {
   navigate(RouteName.Library, query);
 }
 
+function navigateToDuplicates() {
+  navigate(RouteName.Duplicates);
+}
+
 function navigateToTags() {
   navigate(RouteName.BookTag);
 }
 
-export { LibraryMode, navigate, navigateToLibrary, navigateToTags, RouteName, router };
+export {
+  LibraryMode,
+  navigate,
+  navigateToDuplicates,
+  navigateToLibrary,
+  navigateToTags,
+  RouteName,
+  router,
+};
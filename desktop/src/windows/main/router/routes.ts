@@ -1,5 +1,6 @@
 export const enum RouteName {
   BookTag = 'tag',
   Collection = 'collection',
+  Duplicates = 'duplicates',
   Library = 'library',
 }
@@ -6,8 +6,19 @@ export const useCollectionStore = defineStore('collection', () => {
     lazy: true,
   });
 
+  function remove(id: number) {
+    collections.state.value = collections.state.value.filter((it) => it.id !== id);
+  }
+
+  function update(collection: BookCollection) {
+    const others = collections.state.value.filter((it) => it.id !== collection.id);
+    collections.state.value = [...others, collection];
+  }
+
   return {
     collections: collections.state,
     load: collections.execute,
+    remove,
+    update,
   };
 });
@@ -1,4 +1,6 @@
 import { defineStore } from 'pinia';
+import { debounce } from 'lodash-es';
+import { searchBooks } from '@/lib/commands';
 import { Library, type LibraryBookImpl } from '../lib/library';
 
 export const useLibraryStore = defineStore('library', () => {
@@ -6,9 +8,33 @@ export const useLibraryStore = defineStore('library', () => {
   const library = Library.createRef();
   const selected = shallowRef<Nullish<LibraryBookImpl>>();
 
+  /** Ids of the books matching the filter, or `null` if there's nothing to filter. */
+  const matches = shallowRef<Nullish<Set<number>>>(null);
+
+  // Only the most recent search should ever be applied.
+  let searchId = 0;
+  const search = debounce((query: string) => {
+    const id = ++searchId;
+    if (query.trim().length === 0) {
+      matches.value = null;
+      return;
+    }
+
+    searchBooks(query)
+      .then((books) => {
+        if (id === searchId) {
+          matches.value = new Set(books.map((book) => book.id));
+        }
+      })
+      .catch(handleError);
+  }, 150);
+
+  watch(filter, search);
+
   return {
     library,
     filter,
+    matches,
     selected,
     load: () => library.value.load(),
   };
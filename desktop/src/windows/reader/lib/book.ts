@@ -5,6 +5,7 @@ export class ReaderBookImpl implements Omit<ReaderBook, 'pages'> {
   public readonly id?: number;
   public readonly title: string;
   public readonly path: string;
+  public readonly lastPage?: number;
 
   readonly #pages = new Map<number, ReaderBookPageImpl>();
 
@@ -17,6 +18,7 @@ export class ReaderBookImpl implements Omit<ReaderBook, 'pages'> {
     this.id = book.id;
     this.title = book.title;
     this.path = book.path;
+    this.lastPage = book.lastPage;
 
     for (const page of book.pages) {
       this.#pages.set(page.index, new ReaderBookPageImpl(page));
@@ -1,5 +1,11 @@
+import { debounce } from 'lodash-es';
 import { ReaderBookImpl } from './book';
-import { getCurrentReaderBook } from '@/lib/commands';
+import {
+  getCurrentReaderBook,
+  openNextBook,
+  openPreviousBook,
+  updateReaderProgress,
+} from '@/lib/commands';
 
 export class Reader {
   #book: Nullish<ReaderBookImpl>;
@@ -8,6 +14,11 @@ export class Reader {
 
   #current = 0;
 
+  // Flipping through pages shouldn't flood the backend.
+  readonly #saveProgress = debounce((index: number) => {
+    updateReaderProgress(Reader.windowId, index);
+  }, 500);
+
   private constructor(trigger: Fn) {
     this.#trigger = trigger;
   }
@@ -15,10 +26,11 @@ export class Reader {
   public async load() {
     if (!this.#loading) {
       this.#loading = true;
+      this.#saveProgress.cancel();
       try {
         const book = await getCurrentReaderBook(Reader.windowId);
         this.#book = new ReaderBookImpl(book);
-        this.#current = 0;
+        this.#current = this.#book.lastPage ?? 0;
         this.#trigger();
 
         for await (const index of this.#book.fetch()) {
@@ -46,6 +58,7 @@ export class Reader {
       // The page might have been deleted.
       if (this.#book.has(this.#current)) {
         this.#trigger();
+        this.#saveProgress(this.#current);
         return true;
       }
     }
@@ -102,6 +115,17 @@ export class Reader {
     }
   }
 
+  public async nextBook() {
+    // The progress must be saved before the window moves on to another book.
+    this.#saveProgress.flush();
+    await openNextBook(Reader.windowId);
+  }
+
+  public async previousBook() {
+    this.#saveProgress.flush();
+    await openPreviousBook(Reader.windowId);
+  }
+
   public removePage(name: string) {
     if (this.#book?.removePage(name)) {
       this.next();
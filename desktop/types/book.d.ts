@@ -1,14 +1,40 @@
+interface BookCacheStats {
+  readonly capacity: number;
+  readonly evicted: {
+    readonly capacity: number;
+    readonly idle: number;
+    readonly memory: number;
+  };
+  readonly hits: number;
+  readonly idleTimeoutSecs: number;
+  readonly len: number;
+  readonly maxMemory: number;
+  readonly memory: number;
+  readonly misses: number;
+}
+
 interface LibraryBook {
+  readonly addedAt: Nullish<string>;
   cover: string;
   readonly id: number;
+  readonly language: Nullish<string>;
+  readonly manga: Nullish<boolean>;
+  missing: boolean;
+  readonly number: Nullish<string>;
   readonly path: string;
   rating: number;
   read: boolean;
+  readonly rightToLeft: boolean;
+  readonly series: Nullish<string>;
+  readonly summary: Nullish<string>;
   readonly title: string;
+  readonly volume: Nullish<number>;
+  readonly writer: Nullish<string>;
 }
 
 interface ReaderBook {
   readonly id?: number;
+  readonly lastPage?: number;
   readonly pages: ReaderBookPage[];
   readonly path: string;
   readonly title: string;
@@ -19,4 +45,12 @@ interface ReaderBookPage {
   readonly name: string;
 }
 
+interface ReadingSession {
+  readonly bookId: number;
+  readonly endedAt: Nullish<string>;
+  readonly id: number;
+  readonly pagesViewed: number;
+  readonly startedAt: string;
+}
+
 type ReaderBookStatus = 'not started' | 'pending' | 'done' | 'error';
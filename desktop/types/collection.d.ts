@@ -1,4 +1,14 @@
 interface BookCollection {
   readonly id: number;
   readonly name: string;
+  readonly smartFilter: Nullish<SmartFilter>;
+}
+
+interface SmartFilter {
+  readonly addedAfter?: Nullish<string>;
+  readonly addedWithinDays?: Nullish<number>;
+  readonly folder?: Nullish<string>;
+  readonly minRating?: Nullish<number>;
+  readonly read?: Nullish<boolean>;
+  readonly title?: Nullish<string>;
 }
@@ -0,0 +1,5 @@
+interface DatabaseBackup {
+  readonly createdAt: string;
+  readonly path: string;
+  readonly size: number;
+}
@@ -0,0 +1,11 @@
+interface LibraryFolder {
+  readonly exclude: string[];
+  readonly id: number;
+  readonly path: string;
+}
+
+/** Moves paths starting with `from` under `to` when importing a library. */
+interface PathRewrite {
+  readonly from: string;
+  readonly to: string;
+}
@@ -1,9 +1,19 @@
 type BookAddedPayload = Readonly<LibraryBook>;
 
+interface BookMissingPayload {
+  readonly id: number;
+}
+
 interface BookRemovedPayload {
   readonly id: number;
 }
 
+interface CollectionRemovedPayload {
+  readonly id: number;
+}
+
+type CollectionUpdatedPayload = Readonly<BookCollection>;
+
 interface CoverExtractedPayload {
   readonly id: number;
   readonly path: string;
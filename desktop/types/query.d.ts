@@ -0,0 +1,32 @@
+type LibrarySortKey = 'title' | 'rating' | 'addedAt' | 'lastRead';
+
+type LibrarySortOrder = 'asc' | 'desc';
+
+interface LibraryFilter {
+  readonly collection?: Nullish<number>;
+  readonly folder?: Nullish<string>;
+  readonly maxRating?: Nullish<number>;
+  readonly minRating?: Nullish<number>;
+  readonly read?: Nullish<boolean>;
+  /** Only books with every one of these tags. */
+  readonly tags?: number[];
+}
+
+/** Where a page ends. It should be passed back as is to get the next one. */
+interface LibraryCursor {
+  readonly id: number;
+  readonly value: number | string;
+}
+
+interface LibraryQuery {
+  readonly after?: Nullish<LibraryCursor>;
+  readonly filter?: LibraryFilter;
+  readonly limit?: Nullish<number>;
+  readonly order?: LibrarySortOrder;
+  readonly sort?: LibrarySortKey;
+}
+
+interface LibraryPage {
+  readonly books: LibraryBook[];
+  readonly next: Nullish<LibraryCursor>;
+}
@@ -0,0 +1,9 @@
+interface LibrarySeries {
+  readonly books: number;
+  readonly cover: Nullish<string>;
+  readonly id: number;
+  readonly name: string;
+  /** First book, in reading order, that hasn't been read yet. */
+  readonly nextUnread: Nullish<number>;
+  readonly read: number;
+}
@@ -0,0 +1,4 @@
+interface BookTag {
+  readonly id: number;
+  readonly name: string;
+}
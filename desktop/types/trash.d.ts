@@ -0,0 +1,10 @@
+interface TrashedPage {
+  readonly bookPath: string;
+  readonly deletedAt: string;
+  readonly fileName: string;
+  readonly id: number;
+  readonly pageIndex: number;
+  readonly pageName: string;
+  readonly size: number;
+  readonly wasCover: boolean;
+}
@@ -0,0 +1,8 @@
+ALTER TABLE books DROP COLUMN series;
+ALTER TABLE books DROP COLUMN number;
+ALTER TABLE books DROP COLUMN volume;
+ALTER TABLE books DROP COLUMN summary;
+ALTER TABLE books DROP COLUMN writer;
+ALTER TABLE books DROP COLUMN language;
+ALTER TABLE books DROP COLUMN manga;
+ALTER TABLE books DROP COLUMN right_to_left;
@@ -0,0 +1,8 @@
+ALTER TABLE books ADD COLUMN series TEXT;
+ALTER TABLE books ADD COLUMN number TEXT;
+ALTER TABLE books ADD COLUMN volume INTEGER;
+ALTER TABLE books ADD COLUMN summary TEXT;
+ALTER TABLE books ADD COLUMN writer TEXT;
+ALTER TABLE books ADD COLUMN language TEXT;
+ALTER TABLE books ADD COLUMN manga BOOLEAN;
+ALTER TABLE books ADD COLUMN right_to_left BOOLEAN NOT NULL DEFAULT FALSE;
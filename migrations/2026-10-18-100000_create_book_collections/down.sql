@@ -0,0 +1 @@
+DROP TABLE book_collections
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE book_collections (
+    book_id INTEGER NOT NULL REFERENCES books (id) ON DELETE CASCADE,
+    collection_id INTEGER NOT NULL REFERENCES collections (id) ON DELETE CASCADE,
+    PRIMARY KEY (book_id, collection_id)
+)
\ No newline at end of file
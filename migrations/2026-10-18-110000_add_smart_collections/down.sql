@@ -0,0 +1,2 @@
+ALTER TABLE books DROP COLUMN added_at;
+ALTER TABLE collections DROP COLUMN smart_filter;
\ No newline at end of file
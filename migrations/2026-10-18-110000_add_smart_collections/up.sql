@@ -0,0 +1,2 @@
+ALTER TABLE books ADD COLUMN added_at TEXT;
+ALTER TABLE collections ADD COLUMN smart_filter TEXT;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE books DROP COLUMN last_page_index;
+ALTER TABLE books DROP COLUMN last_page_name;
\ No newline at end of file
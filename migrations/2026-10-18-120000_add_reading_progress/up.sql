@@ -0,0 +1,2 @@
+ALTER TABLE books ADD COLUMN last_page_index INTEGER;
+ALTER TABLE books ADD COLUMN last_page_name TEXT;
\ No newline at end of file
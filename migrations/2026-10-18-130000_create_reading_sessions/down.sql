@@ -0,0 +1 @@
+DROP TABLE reading_sessions;
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE reading_sessions (
+    id INTEGER PRIMARY KEY NOT NULL,
+    book_id INTEGER NOT NULL REFERENCES books (id) ON DELETE CASCADE,
+    started_at TEXT NOT NULL,
+    ended_at TEXT,
+    pages_viewed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX reading_sessions_book_id ON reading_sessions (book_id);
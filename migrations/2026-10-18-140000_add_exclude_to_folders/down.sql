@@ -0,0 +1 @@
+ALTER TABLE folders DROP COLUMN exclude;
\ No newline at end of file
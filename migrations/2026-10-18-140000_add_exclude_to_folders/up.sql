@@ -0,0 +1 @@
+ALTER TABLE folders ADD COLUMN exclude TEXT NOT NULL DEFAULT '[]';
\ No newline at end of file
@@ -0,0 +1,3 @@
+DROP INDEX books_hash;
+
+ALTER TABLE books DROP COLUMN hash;
@@ -0,0 +1,3 @@
+ALTER TABLE books ADD COLUMN hash TEXT;
+
+CREATE INDEX books_hash ON books (hash);
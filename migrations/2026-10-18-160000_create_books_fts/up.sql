@@ -0,0 +1,10 @@
+CREATE VIRTUAL TABLE books_fts USING fts5 (
+    title,
+    series,
+    writer,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+INSERT INTO books_fts (rowid, title, series, writer)
+SELECT id, title, series, writer FROM books;
@@ -0,0 +1,3 @@
+DROP TABLE series_books;
+
+DROP TABLE series;
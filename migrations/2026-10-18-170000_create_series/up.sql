@@ -0,0 +1,13 @@
+CREATE TABLE series (
+    id INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE series_books (
+    book_id INTEGER PRIMARY KEY NOT NULL REFERENCES books (id) ON DELETE CASCADE,
+    series_id INTEGER NOT NULL REFERENCES series (id) ON DELETE CASCADE,
+    volume INTEGER,
+    chapter DOUBLE
+);
+
+CREATE INDEX series_books_series_id ON series_books (series_id);
@@ -0,0 +1,3 @@
+DROP TABLE book_tags;
+
+DROP TABLE tags;
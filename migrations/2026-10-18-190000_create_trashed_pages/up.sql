@@ -0,0 +1,12 @@
+CREATE TABLE trashed_pages (
+    id INTEGER PRIMARY KEY NOT NULL,
+    book_path TEXT NOT NULL,
+    page_name TEXT NOT NULL,
+    page_index INTEGER NOT NULL,
+    was_cover BOOLEAN NOT NULL DEFAULT FALSE,
+    file_name TEXT NOT NULL UNIQUE,
+    size BIGINT NOT NULL,
+    deleted_at TEXT NOT NULL
+);
+
+CREATE INDEX trashed_pages_book_path ON trashed_pages (book_path);